@@ -1,5 +1,155 @@
+pub mod leaky_relu;
+pub mod relu;
+pub mod sigmoid;
+pub mod softmax;
+pub mod tanh;
+
+use super::blaf::{self, ColumnVector};
+
 /// Activation function trait
-/// It allows to define an activation function
-pub trait ActivationFunction {
+/// It allows to define an activation function and its first derivative.
+/// By default, derivative is approximated by central finite difference,
+/// so implementors knowing the analytic derivative should override it.
+/// Boxed activation functions are cloned with clone_box, given for every cloneable implementor
+pub trait ActivationFunction: ActivationFunctionClone {
     fn activate(&self, x: f64) -> f64;
+
+    /// Compute first derivative of activation function at x
+    fn derivative(&self, x: f64) -> f64 {
+        // Step is relative to magnitude of x to limit round-off error
+        let h: f64 = 1e-6 * x.abs().max(1.0);
+
+        return (self.activate(x + h) - self.activate(x - h)) / (2.0 * h);
+    }
+}
+
+/// Cloning of a boxed activation function
+/// It is implemented for every activation function which is cloneable
+pub trait ActivationFunctionClone {
+    fn clone_box(&self) -> Box<dyn ActivationFunction>;
+}
+
+impl<Function> ActivationFunctionClone for Function
+where
+    Function: 'static + ActivationFunction + Clone,
+{
+    fn clone_box(&self) -> Box<dyn ActivationFunction> {
+        return Box::new(self.clone());
+    }
+}
+
+impl Clone for Box<dyn ActivationFunction> {
+    fn clone(&self) -> Self {
+        return self.clone_box();
+    }
+}
+
+/// Vector activation function trait
+/// It allows to define an activation function depending on all weighted inputs of a layer,
+/// such as softmax, with the product of its Jacobian transpose by a gradient
+pub trait VectorActivation: VectorActivationClone {
+    fn activate(&self, x: &ColumnVector) -> ColumnVector;
+    fn backward(&self, x: &ColumnVector, gradient: &ColumnVector) -> ColumnVector;
+}
+
+/// Cloning of a boxed vector activation function
+/// It is implemented for every vector activation function which is cloneable
+pub trait VectorActivationClone {
+    fn clone_box(&self) -> Box<dyn VectorActivation>;
+}
+
+impl<Function> VectorActivationClone for Function
+where
+    Function: 'static + VectorActivation + Clone,
+{
+    fn clone_box(&self) -> Box<dyn VectorActivation> {
+        return Box::new(self.clone());
+    }
+}
+
+impl Clone for Box<dyn VectorActivation> {
+    fn clone(&self) -> Self {
+        return self.clone_box();
+    }
+}
+
+/// Activation of a layer, either a scalar activation function applied on each neuron,
+/// or a vector activation function applied on the whole layer
+#[derive(Clone)]
+pub enum LayerActivation {
+    Scalar(Box<dyn ActivationFunction>),
+    Vector(Box<dyn VectorActivation>),
+}
+
+impl LayerActivation {
+    /// Apply activation on weighted inputs of layer
+    pub fn activate(&self, x: &ColumnVector) -> ColumnVector {
+        return match self {
+            LayerActivation::Scalar(fun) => blaf::apply_activation_function(fun.as_ref(), x),
+            LayerActivation::Vector(fun) => fun.activate(x),
+        };
+    }
+
+    /// Compute gradient with respect to weighted inputs of layer from gradient with respect
+    /// to its outputs
+    pub fn backward(&self, x: &ColumnVector, gradient: &ColumnVector) -> ColumnVector {
+        return match self {
+            LayerActivation::Scalar(fun) => x
+                .iter()
+                .zip(gradient.iter())
+                .map(|(&elem, value)| value * fun.derivative(elem))
+                .collect(),
+            LayerActivation::Vector(fun) => fun.backward(x, gradient),
+        };
+    }
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Function to check if two numbers are approximatively equal
+    fn approx_equal(value: f64, reference: f64, precision: f64) -> bool {
+        let mut error: f64 = (value - reference).abs();
+
+        if reference != 0.0 {
+            error /= reference.abs();
+        }
+
+        return error < precision;
+    }
+
+    // Activation function without analytic derivative
+    #[derive(Default, Clone)]
+    struct CubeFn {}
+
+    impl ActivationFunction for CubeFn {
+        fn activate(&self, x: f64) -> f64 {
+            return x * x * x;
+        }
+    }
+
+    #[test]
+    fn test_default_derivative() {
+        let cube: CubeFn = CubeFn::default();
+
+        for x in [-100.0, -2.0, -0.5, 0.0, 0.5, 3.0, 1000.0] {
+            assert!((cube.derivative(x) - 3.0 * x * x).abs() < 1e-6 * (1.0 + 3.0 * x * x));
+        }
+
+        assert!(approx_equal(cube.derivative(2.0), 12.0, 1e-6));
+    }
+
+    #[test]
+    fn test_layer_activation_scalar() {
+        let activation: LayerActivation = LayerActivation::Scalar(Box::new(CubeFn::default()));
+        let x: ColumnVector = vec![-1.0, 2.0];
+
+        assert_eq!(activation.activate(&x), vec![-1.0, 8.0]);
+
+        let gradient: ColumnVector = activation.backward(&x, &vec![1.0, 0.5]);
+        assert!(approx_equal(gradient[0], 3.0, 1e-6));
+        assert!(approx_equal(gradient[1], 6.0, 1e-6));
+    }
 }
@@ -0,0 +1,72 @@
+use super::ActivationFunction;
+
+/// Leaky rectified linear unit activation function
+/// It returns x when x is non-negative, and slope * x otherwise
+#[derive(Clone)]
+pub struct LeakyReLU {
+    slope: f64,
+}
+
+impl LeakyReLU {
+    /// Construct a leaky rectified linear unit with given negative slope
+    pub fn new(slope: f64) -> Self {
+        return Self { slope };
+    }
+}
+
+impl Default for LeakyReLU {
+    /// Construct a leaky rectified linear unit with a negative slope of 0.01
+    fn default() -> Self {
+        return Self::new(0.01);
+    }
+}
+
+impl ActivationFunction for LeakyReLU {
+    fn activate(&self, x: f64) -> f64 {
+        if x >= 0.0 {
+            return x;
+        }
+
+        return self.slope * x;
+    }
+
+    fn derivative(&self, x: f64) -> f64 {
+        if x >= 0.0 {
+            return 1.0;
+        }
+
+        return self.slope;
+    }
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_leaky_relu_activate() {
+        let leaky_relu: LeakyReLU = LeakyReLU::new(0.2);
+
+        assert_eq!(leaky_relu.activate(-2.5), -0.5);
+        assert_eq!(leaky_relu.activate(0.0), 0.0);
+        assert_eq!(leaky_relu.activate(3.5), 3.5);
+    }
+
+    #[test]
+    fn test_leaky_relu_derivative() {
+        let leaky_relu: LeakyReLU = LeakyReLU::new(0.2);
+
+        assert_eq!(leaky_relu.derivative(-2.5), 0.2);
+        assert_eq!(leaky_relu.derivative(0.0), 1.0);
+        assert_eq!(leaky_relu.derivative(3.5), 1.0);
+    }
+
+    #[test]
+    fn test_leaky_relu_default() {
+        let leaky_relu: LeakyReLU = LeakyReLU::default();
+
+        assert_eq!(leaky_relu.activate(-100.0), -1.0);
+        assert_eq!(leaky_relu.derivative(-100.0), 0.01);
+    }
+}
@@ -0,0 +1,46 @@
+use super::ActivationFunction;
+
+/// Rectified linear unit activation function, max(x, 0)
+/// Its derivative at zero is taken as zero
+#[derive(Default, Clone)]
+pub struct ReLU {}
+
+impl ActivationFunction for ReLU {
+    fn activate(&self, x: f64) -> f64 {
+        return x.max(0.0);
+    }
+
+    fn derivative(&self, x: f64) -> f64 {
+        if x > 0.0 {
+            return 1.0;
+        }
+
+        return 0.0;
+    }
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_relu_activate() {
+        let relu: ReLU = ReLU::default();
+
+        assert_eq!(relu.activate(-2.5), 0.0);
+        assert_eq!(relu.activate(0.0), 0.0);
+        assert_eq!(relu.activate(3.5), 3.5);
+    }
+
+    #[test]
+    fn test_relu_derivative() {
+        let relu: ReLU = ReLU::default();
+
+        assert_eq!(relu.derivative(-2.5), 0.0);
+        assert_eq!(relu.derivative(3.5), 1.0);
+
+        // Derivative is ambiguous at zero, it is chosen to be zero
+        assert_eq!(relu.derivative(0.0), 0.0);
+    }
+}
@@ -0,0 +1,89 @@
+use super::ActivationFunction;
+
+/// Sigmoid activation function, 1 / (1 + exp(-x))
+#[derive(Default, Clone)]
+pub struct Sigmoid {}
+
+impl ActivationFunction for Sigmoid {
+    fn activate(&self, x: f64) -> f64 {
+        // Exponential is only computed on non-positive numbers to avoid overflow
+        if x >= 0.0 {
+            return 1.0 / (1.0 + (-x).exp());
+        }
+
+        let exp_x: f64 = x.exp();
+
+        return exp_x / (1.0 + exp_x);
+    }
+
+    fn derivative(&self, x: f64) -> f64 {
+        let s: f64 = self.activate(x);
+
+        return s * (1.0 - s);
+    }
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Function to check if two numbers are approximatively equal
+    fn approx_equal(value: f64, reference: f64, precision: f64) -> bool {
+        let mut error: f64 = (value - reference).abs();
+
+        if reference != 0.0 {
+            error /= reference.abs();
+        }
+
+        return error < precision;
+    }
+
+    #[test]
+    fn test_sigmoid_activate() {
+        let sigmoid: Sigmoid = Sigmoid::default();
+
+        assert_eq!(sigmoid.activate(0.0), 0.5);
+        assert!(approx_equal(
+            sigmoid.activate(2.0),
+            1.0 / (1.0 + (-2.0_f64).exp()),
+            1e-12
+        ));
+        assert!(approx_equal(
+            sigmoid.activate(-2.0),
+            1.0 / (1.0 + 2.0_f64.exp()),
+            1e-12
+        ));
+
+        // No overflow for large numbers
+        assert_eq!(sigmoid.activate(-1000.0), 0.0);
+        assert_eq!(sigmoid.activate(1000.0), 1.0);
+    }
+
+    #[test]
+    fn test_sigmoid_is_monotonic() {
+        let sigmoid: Sigmoid = Sigmoid::default();
+
+        let values: Vec<f64> = (-50..50)
+            .map(|index| sigmoid.activate(index as f64 * 0.2))
+            .collect();
+
+        for id in 1..values.len() {
+            assert!(values[id] > values[id - 1]);
+        }
+    }
+
+    #[test]
+    fn test_sigmoid_derivative() {
+        let sigmoid: Sigmoid = Sigmoid::default();
+
+        assert!(approx_equal(sigmoid.derivative(0.0), 0.25, 1e-12));
+
+        for x in [-3.0, -0.5, 1.0, 4.0] {
+            let h: f64 = 1e-6;
+            let reference: f64 = (sigmoid.activate(x + h) - sigmoid.activate(x - h)) / (2.0 * h);
+
+            assert!(approx_equal(sigmoid.derivative(x), reference, 1e-6));
+        }
+    }
+}
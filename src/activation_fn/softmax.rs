@@ -0,0 +1,90 @@
+use super::super::blaf::{self, ColumnVector};
+use super::VectorActivation;
+
+/// Softmax activation function, exp(x_i) / sum(exp(x_j)) computed on x shifted by its maximum
+/// for numerical stability
+#[derive(Default, Clone)]
+pub struct Softmax {}
+
+impl VectorActivation for Softmax {
+    fn activate(&self, x: &ColumnVector) -> ColumnVector {
+        return blaf::softmax(x);
+    }
+
+    fn backward(&self, x: &ColumnVector, gradient: &ColumnVector) -> ColumnVector {
+        // Jacobian of softmax s is diag(s) - s*s^T, which is symmetric
+        let s: ColumnVector = blaf::softmax(x);
+        let dot: f64 = s
+            .iter()
+            .zip(gradient.iter())
+            .map(|(a, b)| a * b)
+            .sum::<f64>();
+
+        return s
+            .iter()
+            .zip(gradient.iter())
+            .map(|(s_elem, value)| s_elem * (value - dot))
+            .collect();
+    }
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Function to check if two numbers are approximatively equal
+    fn approx_equal(value: f64, reference: f64, precision: f64) -> bool {
+        let mut error: f64 = (value - reference).abs();
+
+        if reference != 0.0 {
+            error /= reference.abs();
+        }
+
+        return error < precision;
+    }
+
+    #[test]
+    fn test_softmax_activate() {
+        let softmax: Softmax = Softmax::default();
+
+        let y: ColumnVector = softmax.activate(&vec![1.0, -2.0, 3.0, 700.0, 710.0]);
+
+        assert_eq!(y.len(), 5);
+        assert!(approx_equal(y.iter().sum::<f64>(), 1.0, 1e-12));
+        assert!(y.iter().all(|elem| elem.is_finite() && *elem >= 0.0));
+        assert!(y[4] > y[3]);
+    }
+
+    #[test]
+    fn test_softmax_backward() {
+        let softmax: Softmax = Softmax::default();
+
+        let x: ColumnVector = vec![0.5, -1.0, 2.0];
+        let gradient: ColumnVector = vec![1.0, -2.0, 0.5];
+
+        let result: ColumnVector = softmax.backward(&x, &gradient);
+
+        // Compare with finite differences of gradient . softmax(x)
+        let h: f64 = 1e-6;
+
+        for id in 0..x.len() {
+            let mut x_plus: ColumnVector = x.clone();
+            let mut x_minus: ColumnVector = x.clone();
+            x_plus[id] += h;
+            x_minus[id] -= h;
+
+            let f = |v: &ColumnVector| -> f64 {
+                return softmax
+                    .activate(v)
+                    .iter()
+                    .zip(gradient.iter())
+                    .map(|(a, b)| a * b)
+                    .sum::<f64>();
+            };
+
+            let reference: f64 = (f(&x_plus) - f(&x_minus)) / (2.0 * h);
+            assert!((result[id] - reference).abs() < 1e-6);
+        }
+    }
+}
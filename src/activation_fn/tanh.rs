@@ -0,0 +1,48 @@
+use super::ActivationFunction;
+
+/// Hyperbolic tangent activation function
+#[derive(Default, Clone)]
+pub struct Tanh {}
+
+impl ActivationFunction for Tanh {
+    fn activate(&self, x: f64) -> f64 {
+        return x.tanh();
+    }
+
+    fn derivative(&self, x: f64) -> f64 {
+        return 1.0 - x.tanh().powi(2);
+    }
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tanh_activate() {
+        let tanh: Tanh = Tanh::default();
+
+        assert_eq!(tanh.activate(0.0), 0.0);
+
+        for x in [0.1, 0.5, 1.0, 3.0, 20.0] {
+            assert_eq!(tanh.activate(-x), -tanh.activate(x));
+        }
+    }
+
+    #[test]
+    fn test_tanh_derivative() {
+        let tanh: Tanh = Tanh::default();
+
+        assert_eq!(tanh.derivative(0.0), 1.0);
+
+        for x in [0.1, 0.5, 1.0, 3.0] {
+            assert!(tanh.derivative(x) < tanh.derivative(0.0));
+            assert!(tanh.derivative(-x) < tanh.derivative(0.0));
+
+            let h: f64 = 1e-6;
+            let reference: f64 = (tanh.activate(x + h) - tanh.activate(x - h)) / (2.0 * h);
+            assert!((tanh.derivative(x) - reference).abs() < 1e-6);
+        }
+    }
+}
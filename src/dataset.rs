@@ -0,0 +1,294 @@
+use super::blaf::ColumnVector;
+use super::num_gen::{next_normal, next_random, seed_state};
+
+/// Get class of a target, i.e. index of its greatest element
+/// Target is expected to be a one-hot encoded column vector
+pub fn class_of(target: &ColumnVector) -> usize {
+    let mut class: usize = 0;
+
+    for (index, value) in target.iter().enumerate() {
+        if *value > target[class] {
+            class = index;
+        }
+    }
+
+    return class;
+}
+
+/// Shuffle indices in place with Fisher-Yates algorithm
+fn shuffle(indices: &mut [usize], state: &mut u64) {
+    for id in (1..indices.len()).rev() {
+        let other: usize = (next_random(state) % (id as u64 + 1)) as usize;
+        indices.swap(id, other);
+    }
+}
+
+/// Compute class-balanced weights from targets of a dataset
+/// Weight of each class is inversely proportional to its frequency in the dataset,
+/// and weights are normalized to sum to number of classes.
+/// A class absent of dataset has a zero weight
+pub fn class_weights(targets: &[ColumnVector], num_classes: usize) -> Vec<f64> {
+    let mut counts: Vec<usize> = vec![0; num_classes];
+
+    for target in targets.iter() {
+        let class: usize = class_of(target);
+
+        if class < num_classes {
+            counts[class] += 1;
+        }
+    }
+
+    let mut weights: Vec<f64> = counts
+        .iter()
+        .map(|&count| if count > 0 { 1.0 / count as f64 } else { 0.0 })
+        .collect();
+
+    let sum: f64 = weights.iter().sum();
+
+    if sum > 0.0 {
+        let factor: f64 = num_classes as f64 / sum;
+        weights.iter_mut().for_each(|weight| *weight *= factor);
+    }
+
+    return weights;
+}
+
+/// Split a dataset into batches preserving the overall class distribution
+/// Samples of each class are shuffled, then spread evenly along the dataset so that
+/// consecutive batches contain classes in the same proportions as the whole dataset.
+/// Last batch is smaller when number of samples is not a multiple of batch size
+pub fn stratified_batches(
+    inputs: &[ColumnVector],
+    targets: &[ColumnVector],
+    batch_size: usize,
+    num_classes: usize,
+    seed: u64,
+) -> Vec<(Vec<ColumnVector>, Vec<ColumnVector>)> {
+    let nb_samples: usize = inputs.len().min(targets.len());
+
+    if batch_size == 0 || nb_samples == 0 {
+        return Vec::new();
+    }
+
+    let mut state: u64 = seed_state(seed);
+
+    let mut classes: Vec<Vec<usize>> = vec![Vec::new(); num_classes.max(1)];
+
+    for (index, target) in targets.iter().enumerate().take(nb_samples) {
+        let class: usize = class_of(target).min(classes.len() - 1);
+        classes[class].push(index);
+    }
+
+    // Each sample is ordered by its relative rank inside its class
+    let mut ordered: Vec<(f64, usize)> = Vec::with_capacity(nb_samples);
+
+    for indices in classes.iter_mut() {
+        shuffle(indices, &mut state);
+
+        let count: f64 = indices.len() as f64;
+
+        for (rank, &index) in indices.iter().enumerate() {
+            ordered.push(((rank as f64 + 0.5) / count, index));
+        }
+    }
+
+    ordered.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+    return ordered
+        .chunks(batch_size)
+        .map(|chunk| {
+            return (
+                chunk
+                    .iter()
+                    .map(|&(_, index)| inputs[index].clone())
+                    .collect(),
+                chunk
+                    .iter()
+                    .map(|&(_, index)| targets[index].clone())
+                    .collect(),
+            );
+        })
+        .collect();
+}
+
+/// Generate a 2D spiral classification dataset with one-hot targets
+/// Samples are spread over classes, each class being an arm of spiral whose angle is
+/// perturbed by gaussian noise with given standard deviation.
+/// It is a reproducible benchmark which is not linearly separable
+pub fn make_spiral(
+    samples: usize,
+    classes: usize,
+    noise: f64,
+    seed: u64,
+) -> (Vec<ColumnVector>, Vec<ColumnVector>) {
+    let mut inputs: Vec<ColumnVector> = Vec::with_capacity(samples);
+    let mut targets: Vec<ColumnVector> = Vec::with_capacity(samples);
+
+    if classes == 0 {
+        return (inputs, targets);
+    }
+
+    let mut state: u64 = seed_state(seed);
+
+    for index in 0..samples {
+        let class: usize = index % classes;
+
+        // Number of samples of this class and rank of current sample in it
+        let nb_class_samples: usize = samples / classes + usize::from(class < samples % classes);
+        let rank: f64 = (index / classes) as f64 / nb_class_samples.max(1) as f64;
+
+        let radius: f64 = rank;
+        let angle: f64 = 4.0 * (class as f64 + rank) + noise * next_normal(&mut state);
+
+        inputs.push(vec![radius * angle.sin(), radius * angle.cos()]);
+
+        let mut target: ColumnVector = vec![0.0; classes];
+        target[class] = 1.0;
+        targets.push(target);
+    }
+
+    return (inputs, targets);
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Function to check if two numbers are approximatively equal
+    fn approx_equal(value: f64, reference: f64, precision: f64) -> bool {
+        let mut error: f64 = (value - reference).abs();
+
+        if reference != 0.0 {
+            error /= reference.abs();
+        }
+
+        return error < precision;
+    }
+
+    #[test]
+    fn test_class_weights_with_imbalanced_dataset() {
+        let mut targets: Vec<ColumnVector> = vec![vec![1.0, 0.0]; 90];
+        targets.extend(vec![vec![0.0, 1.0]; 10]);
+
+        let weights: Vec<f64> = class_weights(&targets, 2);
+
+        assert_eq!(weights.len(), 2);
+        assert!(approx_equal(weights.iter().sum::<f64>(), 2.0, 0.01));
+        assert!(approx_equal(weights[1] / weights[0], 9.0, 0.01));
+        assert!(approx_equal(weights[0], 0.2, 0.01));
+        assert!(approx_equal(weights[1], 1.8, 0.01));
+    }
+
+    #[test]
+    fn test_class_weights_with_missing_class() {
+        let targets: Vec<ColumnVector> = vec![vec![1.0, 0.0, 0.0], vec![0.0, 1.0, 0.0]];
+
+        let weights: Vec<f64> = class_weights(&targets, 3);
+
+        assert!(approx_equal(weights[0], 1.5, 0.01));
+        assert!(approx_equal(weights[1], 1.5, 0.01));
+        assert!(approx_equal(weights[2], 0.0, 0.01));
+    }
+
+    #[test]
+    fn test_stratified_batches() {
+        let mut inputs: Vec<ColumnVector> = Vec::with_capacity(100);
+        let mut targets: Vec<ColumnVector> = Vec::with_capacity(100);
+
+        for index in 0..100 {
+            inputs.push(vec![index as f64]);
+
+            if index % 5 == 0 {
+                targets.push(vec![0.0, 1.0]);
+            } else {
+                targets.push(vec![1.0, 0.0]);
+            }
+        }
+
+        let batches: Vec<(Vec<ColumnVector>, Vec<ColumnVector>)> =
+            stratified_batches(&inputs, &targets, 10, 2, 42);
+
+        assert_eq!(batches.len(), 10);
+
+        let mut seen: Vec<bool> = vec![false; 100];
+
+        for (batch_inputs, batch_targets) in batches.iter() {
+            assert_eq!(batch_inputs.len(), 10);
+            assert_eq!(batch_targets.len(), 10);
+
+            // Global proportion of second class is 20%
+            let nb_minority: usize = batch_targets
+                .iter()
+                .filter(|target| class_of(target) == 1)
+                .count();
+            assert!((1..=3).contains(&nb_minority));
+
+            // Inputs and targets stay paired
+            for (input, target) in batch_inputs.iter().zip(batch_targets.iter()) {
+                let index: usize = input[0] as usize;
+
+                assert!(!seen[index]);
+                seen[index] = true;
+                assert_eq!(target, &targets[index]);
+            }
+        }
+
+        assert!(seen.iter().all(|&elem| elem));
+    }
+
+    #[test]
+    fn test_stratified_batches_is_reproducible() {
+        let inputs: Vec<ColumnVector> = (0..30).map(|index| vec![index as f64]).collect();
+        let targets: Vec<ColumnVector> = (0..30)
+            .map(|index| {
+                let mut target: ColumnVector = vec![0.0; 3];
+                target[index % 3] = 1.0;
+                return target;
+            })
+            .collect();
+
+        let first: Vec<(Vec<ColumnVector>, Vec<ColumnVector>)> =
+            stratified_batches(&inputs, &targets, 7, 3, 7);
+        let second: Vec<(Vec<ColumnVector>, Vec<ColumnVector>)> =
+            stratified_batches(&inputs, &targets, 7, 3, 7);
+
+        assert_eq!(first.len(), 5);
+        assert_eq!(first.last().unwrap().0.len(), 2);
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn test_make_spiral() {
+        let (inputs, targets) = make_spiral(100, 3, 0.2, 5);
+
+        assert_eq!(inputs.len(), 100);
+        assert_eq!(targets.len(), 100);
+
+        for (input, target) in inputs.iter().zip(targets.iter()) {
+            assert_eq!(input.len(), 2);
+            assert!(input[0].hypot(input[1]) <= 1.0);
+
+            assert_eq!(target.len(), 3);
+            assert_eq!(target.iter().filter(|&&value| value == 1.0).count(), 1);
+            assert_eq!(target.iter().filter(|&&value| value == 0.0).count(), 2);
+        }
+
+        // Class sizes differ by at most one
+        let weights: Vec<f64> = class_weights(&targets, 3);
+        assert!(weights
+            .iter()
+            .all(|weight| approx_equal(*weight, 1.0, 0.05)));
+    }
+
+    #[test]
+    fn test_make_spiral_is_reproducible() {
+        let (first_inputs, first_targets) = make_spiral(50, 2, 0.1, 11);
+        let (second_inputs, second_targets) = make_spiral(50, 2, 0.1, 11);
+        let (other_inputs, _) = make_spiral(50, 2, 0.1, 12);
+
+        assert_eq!(first_inputs, second_inputs);
+        assert_eq!(first_targets, second_targets);
+        assert_ne!(first_inputs, other_inputs);
+    }
+}
@@ -0,0 +1,90 @@
+use std::fmt;
+
+/// Error of nenufar crate
+/// Each variant is a failure mode, its human-readable message is given by Display
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NenufarError {
+    /// Size of input is not consistent with number of inputs of neural network
+    DimensionMismatch { expected: usize, found: usize },
+    /// Size of vector multiplied by a matrix is not consistent with its number of columns
+    ColumnMismatch { expected: usize, found: usize },
+    /// Size of vector added to a matrix-vector product is not consistent with number of rows
+    RowMismatch { expected: usize, found: usize },
+    /// Topology has no layer after input layer
+    EmptyTopology,
+    /// Topology has no input
+    MissingInputLayer,
+    /// Topology has no output layer
+    /// TopologyBuilder::build does not return it, as last added layer is the output layer
+    MissingOutputLayer,
+    /// Output layer of topology is followed by other layers
+    MisplacedOutputLayer,
+}
+
+impl fmt::Display for NenufarError {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        let message: &str = match self {
+            NenufarError::DimensionMismatch { .. } => {
+                "Number of input are not consistent with topology of neural network"
+            }
+            NenufarError::ColumnMismatch { .. } => {
+                "Number of columns of matrix and size of first vector must be equal"
+            }
+            NenufarError::RowMismatch { .. } => {
+                "Number of rows of matrix and size of second vector must be equal"
+            }
+            NenufarError::EmptyTopology => "There is no hidden layer in your neural network",
+            NenufarError::MissingInputLayer => "There is no input layer in your neural network",
+            NenufarError::MissingOutputLayer => "There is no output layer in your neural network",
+            NenufarError::MisplacedOutputLayer => {
+                "Output layer must be the last layer of your neural network"
+            }
+        };
+
+        return write!(formatter, "{}", message);
+    }
+}
+
+impl std::error::Error for NenufarError {}
+
+/// Conversion into message, so that functions returning String errors can propagate it
+impl From<NenufarError> for String {
+    fn from(error: NenufarError) -> Self {
+        return error.to_string();
+    }
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display_nenufar_error() {
+        let error: NenufarError = NenufarError::DimensionMismatch {
+            expected: 2,
+            found: 3,
+        };
+
+        assert_eq!(
+            error.to_string(),
+            "Number of input are not consistent with topology of neural network"
+        );
+
+        assert_eq!(
+            NenufarError::MissingInputLayer.to_string(),
+            "There is no input layer in your neural network"
+        );
+
+        assert_eq!(
+            NenufarError::MissingOutputLayer.to_string(),
+            "There is no output layer in your neural network"
+        );
+    }
+
+    #[test]
+    fn test_convert_nenufar_error_into_string() {
+        let message: String = String::from(NenufarError::EmptyTopology);
+        assert_eq!(message, "There is no hidden layer in your neural network");
+    }
+}
@@ -0,0 +1,250 @@
+use super::blaf::ColumnVector;
+use super::dataset::class_of;
+
+/// Compute reliability curve of a probabilistic classifier
+/// Predictions are grouped in bins of equal width according to their confidence, i.e. their
+/// greatest probability. For each bin, this function return mean confidence, accuracy and
+/// number of predictions. Empty bins have zero mean confidence and accuracy
+pub fn reliability_curve(
+    predictions: &[ColumnVector],
+    targets: &[ColumnVector],
+    num_bins: usize,
+) -> Result<Vec<(f64, f64, usize)>, String> {
+    if predictions.len() != targets.len() {
+        return Err(String::from(
+            "Number of predictions and number of targets must be equal",
+        ));
+    }
+
+    if predictions.iter().any(|prediction| prediction.is_empty()) {
+        return Err(String::from("Predictions must not be empty"));
+    }
+
+    if num_bins == 0 {
+        return Ok(Vec::new());
+    }
+
+    let mut confidences: Vec<f64> = vec![0.0; num_bins];
+    let mut nb_corrects: Vec<usize> = vec![0; num_bins];
+    let mut counts: Vec<usize> = vec![0; num_bins];
+
+    for (prediction, target) in predictions.iter().zip(targets.iter()) {
+        let class: usize = class_of(prediction);
+        let confidence: f64 = prediction[class];
+
+        let bin: usize = ((confidence * num_bins as f64) as usize).min(num_bins - 1);
+
+        confidences[bin] += confidence;
+        counts[bin] += 1;
+
+        if class == class_of(target) {
+            nb_corrects[bin] += 1;
+        }
+    }
+
+    return Ok((0..num_bins)
+        .map(|bin| {
+            if counts[bin] == 0 {
+                return (0.0, 0.0, 0);
+            }
+
+            let count: f64 = counts[bin] as f64;
+
+            return (
+                confidences[bin] / count,
+                nb_corrects[bin] as f64 / count,
+                counts[bin],
+            );
+        })
+        .collect());
+}
+
+/// Compute Cohen's kappa between classifications of two models
+/// It measures agreement between predicted classes of both models beyond agreement expected
+/// by chance from frequencies of their classes: 1 for perfect agreement, 0 for agreement by
+/// chance only. Two models predicting always the same single class have a kappa of 1
+pub fn cohens_kappa(preds_a: &[ColumnVector], preds_b: &[ColumnVector]) -> Result<f64, String> {
+    if preds_a.is_empty() || preds_a.len() != preds_b.len() {
+        return Err(String::from(
+            "Numbers of predictions of both models must be equal and non zero",
+        ));
+    }
+
+    if preds_a
+        .iter()
+        .chain(preds_b.iter())
+        .any(|prediction| prediction.is_empty())
+    {
+        return Err(String::from("Predictions must not be empty"));
+    }
+
+    let nb_predictions: usize = preds_a.len();
+
+    let num_classes: usize = preds_a
+        .iter()
+        .chain(preds_b.iter())
+        .map(|prediction| prediction.len())
+        .max()
+        .unwrap();
+
+    let mut counts_a: Vec<usize> = vec![0; num_classes];
+    let mut counts_b: Vec<usize> = vec![0; num_classes];
+    let mut nb_agreements: usize = 0;
+
+    for (prediction_a, prediction_b) in preds_a.iter().zip(preds_b.iter()) {
+        let class_a: usize = class_of(prediction_a);
+        let class_b: usize = class_of(prediction_b);
+
+        counts_a[class_a] += 1;
+        counts_b[class_b] += 1;
+
+        if class_a == class_b {
+            nb_agreements += 1;
+        }
+    }
+
+    let size: f64 = nb_predictions as f64;
+    let observed: f64 = nb_agreements as f64 / size;
+    let expected: f64 = counts_a
+        .iter()
+        .zip(counts_b.iter())
+        .map(|(&count_a, &count_b)| (count_a as f64 / size) * (count_b as f64 / size))
+        .sum::<f64>();
+
+    if expected >= 1.0 {
+        return Ok(1.0);
+    }
+
+    return Ok((observed - expected) / (1.0 - expected));
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Function to check if two numbers are approximatively equal
+    fn approx_equal(value: f64, reference: f64, precision: f64) -> bool {
+        let mut error: f64 = (value - reference).abs();
+
+        if reference != 0.0 {
+            error /= reference.abs();
+        }
+
+        return error < precision;
+    }
+
+    #[test]
+    fn test_reliability_curve() {
+        let predictions: Vec<ColumnVector> = vec![
+            vec![0.9, 0.1],
+            vec![0.1, 0.9],
+            vec![0.8, 0.2],
+            vec![0.4, 0.6],
+            vec![0.55, 0.45],
+            vec![1.0, 0.0],
+        ];
+        let targets: Vec<ColumnVector> = vec![
+            vec![1.0, 0.0],
+            vec![1.0, 0.0],
+            vec![1.0, 0.0],
+            vec![0.0, 1.0],
+            vec![0.0, 1.0],
+            vec![1.0, 0.0],
+        ];
+
+        let curve: Vec<(f64, f64, usize)> = reliability_curve(&predictions, &targets, 5).unwrap();
+
+        assert_eq!(curve.len(), 5);
+
+        // Bins [0, 0.2) and [0.2, 0.4) are empty
+        assert_eq!(curve[0], (0.0, 0.0, 0));
+        assert_eq!(curve[1], (0.0, 0.0, 0));
+
+        // Bin [0.4, 0.6) has confidence 0.55 (wrong)
+        assert_eq!(curve[2].2, 1);
+        assert!(approx_equal(curve[2].0, 0.55, 0.01));
+        assert!(approx_equal(curve[2].1, 0.0, 0.01));
+
+        // Bin [0.6, 0.8) has confidence 0.6 (right)
+        assert_eq!(curve[3].2, 1);
+        assert!(approx_equal(curve[3].0, 0.6, 0.01));
+        assert!(approx_equal(curve[3].1, 1.0, 0.01));
+
+        // Bin [0.8, 1.0] has confidences 0.9 (right), 0.9 (wrong), 0.8 (right) and 1.0 (right)
+        assert_eq!(curve[4].2, 4);
+        assert!(approx_equal(curve[4].0, 0.9, 0.01));
+        assert!(approx_equal(curve[4].1, 0.75, 0.01));
+    }
+
+    #[test]
+    fn test_reliability_curve_return_error() {
+        let targets: Vec<ColumnVector> = vec![vec![1.0, 0.0], vec![0.0, 1.0]];
+
+        assert!(reliability_curve(&[vec![0.9, 0.1]], &targets, 5).is_err());
+        assert!(reliability_curve(&[vec![0.9, 0.1], vec![]], &targets, 5).is_err());
+    }
+
+    #[test]
+    fn test_cohens_kappa_perfect_agreement() {
+        let preds_a: Vec<ColumnVector> = vec![
+            vec![0.9, 0.1, 0.0],
+            vec![0.2, 0.7, 0.1],
+            vec![0.1, 0.2, 0.7],
+            vec![0.6, 0.3, 0.1],
+        ];
+        let preds_b: Vec<ColumnVector> = vec![
+            vec![0.5, 0.4, 0.1],
+            vec![0.0, 1.0, 0.0],
+            vec![0.3, 0.3, 0.4],
+            vec![0.8, 0.1, 0.1],
+        ];
+
+        match cohens_kappa(&preds_a, &preds_b) {
+            Ok(kappa) => assert!(approx_equal(kappa, 1.0, 1e-9)),
+            Err(_) => assert!(false),
+        }
+    }
+
+    #[test]
+    fn test_cohens_kappa_chance_agreement() {
+        let one_hot = |class: usize| -> ColumnVector {
+            let mut prediction: ColumnVector = vec![0.0; 2];
+            prediction[class] = 1.0;
+            return prediction;
+        };
+
+        // Half of predictions agree, as expected by chance with balanced classes
+        let preds_a: Vec<ColumnVector> = [0, 0, 1, 1, 0, 0, 1, 1]
+            .iter()
+            .map(|&c| one_hot(c))
+            .collect();
+        let preds_b: Vec<ColumnVector> = [0, 1, 0, 1, 0, 1, 0, 1]
+            .iter()
+            .map(|&c| one_hot(c))
+            .collect();
+
+        assert!(cohens_kappa(&preds_a, &preds_b).unwrap().abs() < 1e-9);
+
+        // Systematic disagreement gives a negative kappa
+        let preds_c: Vec<ColumnVector> = [1, 1, 0, 0, 1, 1, 0, 0]
+            .iter()
+            .map(|&c| one_hot(c))
+            .collect();
+
+        assert!(approx_equal(
+            cohens_kappa(&preds_a, &preds_c).unwrap(),
+            -1.0,
+            1e-9
+        ));
+    }
+
+    #[test]
+    fn test_cohens_kappa_return_error() {
+        let preds: Vec<ColumnVector> = vec![vec![0.9, 0.1], vec![0.2, 0.8]];
+
+        assert!(cohens_kappa(&[], &[]).is_err());
+        assert!(cohens_kappa(&preds, &preds[..1]).is_err());
+        assert!(cohens_kappa(&[vec![], vec![]], &preds).is_err());
+    }
+}
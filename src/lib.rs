@@ -1,8 +1,43 @@
 // Disable temporary warning on dead code
 #![allow(dead_code)]
+// Explicit return statements are the coding style of this crate
+#![allow(clippy::needless_return)]
+// Unit tests use assert!(false) to flag unexpected match arms
+#![cfg_attr(test, allow(clippy::assertions_on_constants))]
 
 mod activation_fn;
 mod blaf;
+mod dataset;
+mod error;
+mod evaluate;
+mod loss;
 mod neural_net;
 mod num_gen;
+mod optimizer;
+mod scheduler;
 mod topology;
+
+pub use activation_fn::leaky_relu::LeakyReLU;
+pub use activation_fn::relu::ReLU;
+pub use activation_fn::sigmoid::Sigmoid;
+pub use activation_fn::softmax::Softmax;
+pub use activation_fn::tanh::Tanh;
+pub use activation_fn::{
+    ActivationFunction, ActivationFunctionClone, LayerActivation, VectorActivation,
+    VectorActivationClone,
+};
+pub use blaf::{ColumnVector, Matrix};
+pub use dataset::{class_of, class_weights, make_spiral, stratified_batches};
+pub use error::NenufarError;
+pub use evaluate::{cohens_kappa, reliability_curve};
+pub use loss::{Distillation, LogCosh, Loss, MeanSquaredError, Reduction};
+pub use neural_net::{gradient_cosine, ForwardPass, LayerTrace, NetworkState, NeuralNet};
+pub use num_gen::he::HeGenerator;
+pub use num_gen::normal::NormalGenerator;
+pub use num_gen::orthogonal::OrthogonalGenerator;
+pub use num_gen::uniform::UniformGenerator;
+pub use num_gen::xavier::XavierGenerator;
+pub use num_gen::{LayerAwareGenerator, NumberGenerator};
+pub use optimizer::{Adagrad, Optimizer, StaleGradientApplier};
+pub use scheduler::{LearningRateScheduler, WarmupDecay};
+pub use topology::{Topology, TopologyBuilder};
@@ -0,0 +1,390 @@
+use super::blaf::{softmax, ColumnVector};
+
+/// Aggregation of per-element losses
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Reduction {
+    Mean,
+    Sum,
+    None,
+}
+
+/// Loss function trait
+/// It allows to define a loss measuring error between prediction and target of same size,
+/// with its gradient with respect to prediction.
+/// Implementors give loss and its derivative for each element, then reduction is made by
+/// provided methods, averaging over elements by default
+pub trait Loss {
+    fn elements(&self, prediction: &ColumnVector, target: &ColumnVector) -> ColumnVector;
+    fn element_gradients(&self, prediction: &ColumnVector, target: &ColumnVector) -> ColumnVector;
+
+    /// Compute mean loss over elements
+    fn loss(&self, prediction: &ColumnVector, target: &ColumnVector) -> f64 {
+        return self.elements(prediction, target).iter().sum::<f64>() / prediction.len() as f64;
+    }
+
+    /// Compute gradient of mean loss over elements
+    fn gradient(&self, prediction: &ColumnVector, target: &ColumnVector) -> ColumnVector {
+        let size: f64 = prediction.len() as f64;
+
+        return self
+            .element_gradients(prediction, target)
+            .iter()
+            .map(|elem| elem / size)
+            .collect();
+    }
+
+    /// Compute loss with given reduction
+    /// Result has a single element for Mean and Sum reductions,
+    /// and one element per prediction element for None reduction
+    fn reduced_loss(
+        &self,
+        prediction: &ColumnVector,
+        target: &ColumnVector,
+        reduction: Reduction,
+    ) -> ColumnVector {
+        let elements: ColumnVector = self.elements(prediction, target);
+
+        return match reduction {
+            Reduction::Mean => vec![elements.iter().sum::<f64>() / elements.len() as f64],
+            Reduction::Sum => vec![elements.iter().sum::<f64>()],
+            Reduction::None => elements,
+        };
+    }
+
+    /// Compute gradient of loss with given reduction
+    /// For None reduction, it is the derivative of each element loss
+    fn reduced_gradient(
+        &self,
+        prediction: &ColumnVector,
+        target: &ColumnVector,
+        reduction: Reduction,
+    ) -> ColumnVector {
+        return match reduction {
+            Reduction::Mean => self.gradient(prediction, target),
+            Reduction::Sum | Reduction::None => self.element_gradients(prediction, target),
+        };
+    }
+}
+
+/// Mean squared error loss, mean of (prediction - target)^2
+#[derive(Default)]
+pub struct MeanSquaredError {}
+
+impl Loss for MeanSquaredError {
+    fn elements(&self, prediction: &ColumnVector, target: &ColumnVector) -> ColumnVector {
+        return prediction
+            .iter()
+            .zip(target.iter())
+            .map(|(p, t)| (p - t).powi(2))
+            .collect();
+    }
+
+    fn element_gradients(&self, prediction: &ColumnVector, target: &ColumnVector) -> ColumnVector {
+        return prediction
+            .iter()
+            .zip(target.iter())
+            .map(|(p, t)| 2.0 * (p - t))
+            .collect();
+    }
+}
+
+/// Log-cosh loss, mean of ln(cosh(prediction - target))
+/// It behaves like mean squared error for small errors and like mean absolute error for
+/// large ones, while being twice differentiable
+#[derive(Default)]
+pub struct LogCosh {}
+
+impl Loss for LogCosh {
+    fn elements(&self, prediction: &ColumnVector, target: &ColumnVector) -> ColumnVector {
+        // ln(cosh(r)) = |r| + ln(1 + exp(-2|r|)) - ln(2) avoids overflow of cosh
+        return prediction
+            .iter()
+            .zip(target.iter())
+            .map(|(p, t)| {
+                let residual: f64 = (p - t).abs();
+                return residual + (-2.0 * residual).exp().ln_1p() - 2.0_f64.ln();
+            })
+            .collect();
+    }
+
+    fn element_gradients(&self, prediction: &ColumnVector, target: &ColumnVector) -> ColumnVector {
+        return prediction
+            .iter()
+            .zip(target.iter())
+            .map(|(p, t)| (p - t).tanh())
+            .collect();
+    }
+}
+
+/// Distillation loss between student logits (prediction) and teacher logits (target)
+/// Both are softened by softmax with temperature, then Kullback-Leibler divergence of
+/// student distribution from teacher one is scaled by temperature^2 so that gradient
+/// magnitude does not depend on temperature
+pub struct Distillation {
+    pub temperature: f64,
+}
+
+impl Distillation {
+    /// Compute softmax of logits divided by temperature
+    fn soften(&self, logits: &ColumnVector) -> ColumnVector {
+        let scaled: ColumnVector = logits.iter().map(|x| x / self.temperature).collect();
+        return softmax(&scaled);
+    }
+}
+
+impl Loss for Distillation {
+    fn elements(&self, prediction: &ColumnVector, target: &ColumnVector) -> ColumnVector {
+        let student: ColumnVector = self.soften(prediction);
+        let teacher: ColumnVector = self.soften(target);
+        let scale: f64 = self.temperature * self.temperature;
+
+        return teacher
+            .iter()
+            .zip(student.iter())
+            .map(|(q, p)| {
+                if *q <= 0.0 {
+                    return 0.0;
+                }
+
+                return scale * q * (q.ln() - p.max(f64::MIN_POSITIVE).ln());
+            })
+            .collect();
+    }
+
+    fn element_gradients(&self, prediction: &ColumnVector, target: &ColumnVector) -> ColumnVector {
+        let student: ColumnVector = self.soften(prediction);
+        let teacher: ColumnVector = self.soften(target);
+
+        return student
+            .iter()
+            .zip(teacher.iter())
+            .map(|(p, q)| self.temperature * (p - q))
+            .collect();
+    }
+
+    /// Divergence is a sum over classes, so it is not averaged over elements
+    fn loss(&self, prediction: &ColumnVector, target: &ColumnVector) -> f64 {
+        return self.elements(prediction, target).iter().sum::<f64>();
+    }
+
+    /// Gradient of divergence with respect to student logits
+    fn gradient(&self, prediction: &ColumnVector, target: &ColumnVector) -> ColumnVector {
+        return self.element_gradients(prediction, target);
+    }
+
+    /// Mean and Sum reductions both give the divergence, as it is not averaged over elements
+    /// None reduction gives the per-class terms of the divergence, which sum to it
+    fn reduced_loss(
+        &self,
+        prediction: &ColumnVector,
+        target: &ColumnVector,
+        reduction: Reduction,
+    ) -> ColumnVector {
+        return match reduction {
+            Reduction::Mean | Reduction::Sum => vec![self.loss(prediction, target)],
+            Reduction::None => self.elements(prediction, target),
+        };
+    }
+
+    /// Softmax couples all logits, so there is no per-element derivative:
+    /// every reduction gives the gradient of the divergence
+    fn reduced_gradient(
+        &self,
+        prediction: &ColumnVector,
+        target: &ColumnVector,
+        _reduction: Reduction,
+    ) -> ColumnVector {
+        return self.gradient(prediction, target);
+    }
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Function to check if two numbers are approximatively equal
+    fn approx_equal(value: f64, reference: f64, precision: f64) -> bool {
+        let mut error: f64 = (value - reference).abs();
+
+        if reference != 0.0 {
+            error /= reference.abs();
+        }
+
+        return error < precision;
+    }
+
+    #[test]
+    fn test_mean_squared_error() {
+        let mse: MeanSquaredError = MeanSquaredError::default();
+        let prediction: ColumnVector = vec![1.5, -2.0, 0.0];
+        let target: ColumnVector = vec![1.0, 1.0, 0.0];
+
+        assert!(approx_equal(
+            mse.loss(&prediction, &target),
+            9.25 / 3.0,
+            0.01
+        ));
+        assert!(mse.loss(&target, &target).abs() < 1e-12);
+
+        let gradient: ColumnVector = mse.gradient(&prediction, &target);
+
+        assert!(approx_equal(gradient[0], 1.0 / 3.0, 0.01));
+        assert!(approx_equal(gradient[1], -2.0, 0.01));
+        assert!(approx_equal(gradient[2], 0.0, 0.01));
+    }
+
+    #[test]
+    fn test_log_cosh_loss_on_identical_vectors() {
+        let log_cosh: LogCosh = LogCosh::default();
+        let x: ColumnVector = vec![1.0, -2.0, 3.0];
+
+        assert!(log_cosh.loss(&x, &x).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_log_cosh_loss_on_small_residuals() {
+        let log_cosh: LogCosh = LogCosh::default();
+        let prediction: ColumnVector = vec![1.01, 2.0];
+        let target: ColumnVector = vec![1.0, 2.02];
+
+        let reference: f64 = (0.5 * 0.01_f64.powi(2) + 0.5 * 0.02_f64.powi(2)) / 2.0;
+
+        assert!(approx_equal(
+            log_cosh.loss(&prediction, &target),
+            reference,
+            0.01
+        ));
+    }
+
+    #[test]
+    fn test_log_cosh_loss_on_large_residuals() {
+        let log_cosh: LogCosh = LogCosh::default();
+        let prediction: ColumnVector = vec![1000.0];
+        let target: ColumnVector = vec![0.0];
+
+        assert!(approx_equal(
+            log_cosh.loss(&prediction, &target),
+            1000.0 - 2.0_f64.ln(),
+            0.01
+        ));
+    }
+
+    #[test]
+    fn test_log_cosh_gradient() {
+        let log_cosh: LogCosh = LogCosh::default();
+        let prediction: ColumnVector = vec![1.5, -2.0, 0.0];
+        let target: ColumnVector = vec![1.0, 1.0, 0.0];
+
+        let gradient: ColumnVector = log_cosh.gradient(&prediction, &target);
+
+        assert_eq!(gradient.len(), 3);
+        assert!(approx_equal(gradient[0], 0.5_f64.tanh() / 3.0, 0.01));
+        assert!(approx_equal(gradient[1], (-3.0_f64).tanh() / 3.0, 0.01));
+        assert!(approx_equal(gradient[2], 0.0, 0.01));
+    }
+
+    #[test]
+    fn test_reduced_loss() {
+        let log_cosh: LogCosh = LogCosh::default();
+        let prediction: ColumnVector = vec![1.5, -2.0, 0.0, 4.0];
+        let target: ColumnVector = vec![1.0, 1.0, 0.0, 1.0];
+
+        let mean: ColumnVector = log_cosh.reduced_loss(&prediction, &target, Reduction::Mean);
+        let sum: ColumnVector = log_cosh.reduced_loss(&prediction, &target, Reduction::Sum);
+        let none: ColumnVector = log_cosh.reduced_loss(&prediction, &target, Reduction::None);
+
+        assert_eq!(mean.len(), 1);
+        assert_eq!(sum.len(), 1);
+        assert_eq!(none.len(), prediction.len());
+
+        assert!(approx_equal(
+            mean[0],
+            log_cosh.loss(&prediction, &target),
+            0.01
+        ));
+        assert!(approx_equal(sum[0], 4.0 * mean[0], 0.01));
+
+        for id in 0..prediction.len() {
+            let reference: f64 = (prediction[id] - target[id]).cosh().ln();
+            assert!(approx_equal(none[id], reference, 0.01));
+        }
+    }
+
+    #[test]
+    fn test_reduced_gradient() {
+        let log_cosh: LogCosh = LogCosh::default();
+        let prediction: ColumnVector = vec![1.5, -2.0];
+        let target: ColumnVector = vec![1.0, 1.0];
+
+        let mean: ColumnVector = log_cosh.reduced_gradient(&prediction, &target, Reduction::Mean);
+        let sum: ColumnVector = log_cosh.reduced_gradient(&prediction, &target, Reduction::Sum);
+
+        for id in 0..prediction.len() {
+            assert!(approx_equal(sum[id], 2.0 * mean[id], 0.01));
+            assert!(approx_equal(
+                sum[id],
+                (prediction[id] - target[id]).tanh(),
+                0.01
+            ));
+        }
+    }
+
+    #[test]
+    fn test_distillation_loss() {
+        let distillation: Distillation = Distillation { temperature: 2.0 };
+        let teacher: ColumnVector = vec![2.0, -1.0, 0.5];
+        let student: ColumnVector = vec![0.5, 1.0, -0.5];
+
+        assert!(distillation.loss(&teacher, &teacher).abs() < 1e-12);
+        assert!(distillation.loss(&student, &teacher) > 0.0);
+
+        for elem in distillation.gradient(&teacher, &teacher).iter() {
+            assert!(elem.abs() < 1e-12);
+        }
+
+        // Gradient is compared with central finite difference of loss
+        let gradient: ColumnVector = distillation.gradient(&student, &teacher);
+        let h: f64 = 1e-6;
+
+        for id in 0..student.len() {
+            let mut forward: ColumnVector = student.clone();
+            forward[id] += h;
+
+            let mut backward: ColumnVector = student.clone();
+            backward[id] -= h;
+
+            let reference: f64 = (distillation.loss(&forward, &teacher)
+                - distillation.loss(&backward, &teacher))
+                / (2.0 * h);
+
+            assert!(approx_equal(gradient[id], reference, 0.01));
+        }
+    }
+    #[test]
+    fn test_distillation_reductions_are_consistent() {
+        let distillation: Distillation = Distillation { temperature: 2.0 };
+        let teacher: ColumnVector = vec![2.0, -1.0, 0.5];
+        let student: ColumnVector = vec![0.5, 1.0, -0.5];
+
+        let loss: f64 = distillation.loss(&student, &teacher);
+        let mean: ColumnVector = distillation.reduced_loss(&student, &teacher, Reduction::Mean);
+        let sum: ColumnVector = distillation.reduced_loss(&student, &teacher, Reduction::Sum);
+        let terms: ColumnVector = distillation.reduced_loss(&student, &teacher, Reduction::None);
+
+        assert_eq!(mean.len(), 1);
+        assert!(approx_equal(mean[0], loss, 1e-12));
+        assert!(approx_equal(sum[0], loss, 1e-12));
+        assert_eq!(terms.len(), 3);
+        assert!(approx_equal(terms.iter().sum::<f64>(), loss, 1e-12));
+
+        let gradient: ColumnVector = distillation.gradient(&student, &teacher);
+
+        for reduction in [Reduction::Mean, Reduction::Sum, Reduction::None] {
+            assert_eq!(
+                distillation.reduced_gradient(&student, &teacher, reduction),
+                gradient
+            );
+        }
+    }
+}
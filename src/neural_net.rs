@@ -1,21 +1,140 @@
-use super::activation_fn::ActivationFunction;
+use super::activation_fn::sigmoid::Sigmoid;
+use super::activation_fn::{ActivationFunction, LayerActivation};
 use super::blaf;
-use super::num_gen::NumberGenerator;
+use super::dataset::class_of;
+use super::error::NenufarError;
+use super::loss::{Loss, MeanSquaredError};
+use super::num_gen::{LayerAwareGenerator, NumberGenerator};
+use super::optimizer::StaleGradientApplier;
 use super::topology::Topology;
 
+use std::io;
+
+/// Trace of a layer during forward pass, it contains weighted inputs of neurons
+/// (pre-activation) and outputs of neurons (post-activation)
+pub struct LayerTrace {
+    pub pre_activation: blaf::ColumnVector,
+    pub post_activation: blaf::ColumnVector,
+}
+
+/// Result of forward pass, it contains for each layer weighted inputs of neurons (z)
+/// and activations of neurons (a)
+pub struct ForwardPass {
+    pub weighted_inputs: Vec<blaf::ColumnVector>,
+    pub activations: Vec<blaf::ColumnVector>,
+}
+
+/// Owned parameter state of a neural network, made of layer dimensions (including input
+/// layer), weights in row-major order and bias of each layer, and calibration temperature.
+/// Activation functions are not part of the state
+#[derive(Clone, Debug, PartialEq)]
+pub struct NetworkState {
+    pub nb_neurons: Vec<usize>,
+    pub weigths: Vec<Vec<f64>>,
+    pub bias: Vec<blaf::ColumnVector>,
+    pub temperature: f64,
+}
+
 /// Neural network is representating by vector of weight matrix, vector of bias vector
 /// and vector of activation function
+#[derive(Clone)]
 pub struct NeuralNet {
     weigths: Vec<blaf::Matrix>,
     bias: Vec<blaf::ColumnVector>,
-    activation_functions: Vec<Box<dyn ActivationFunction>>,
+    activation_functions: Vec<LayerActivation>,
+    temperature: f64,
+}
+
+/// Compute mean cross-entropy between softmax of logits scaled by temperature and targets
+fn temperature_cross_entropy(
+    logits: &[blaf::ColumnVector],
+    targets: &[blaf::ColumnVector],
+    temperature: f64,
+) -> f64 {
+    let total: f64 = logits
+        .iter()
+        .zip(targets.iter())
+        .map(|(logit, target)| {
+            let scaled: blaf::ColumnVector = logit.iter().map(|x| x / temperature).collect();
+
+            return blaf::softmax(&scaled)
+                .iter()
+                .zip(target.iter())
+                .map(|(p, t)| -t * p.max(f64::MIN_POSITIVE).ln())
+                .sum::<f64>();
+        })
+        .sum::<f64>();
+
+    return total / logits.len() as f64;
+}
+
+/// Compute element-wise mean and variance of outputs given in argument
+fn output_statistics(outputs: &[blaf::ColumnVector]) -> (blaf::ColumnVector, blaf::ColumnVector) {
+    let nb_samples: f64 = outputs.len() as f64;
+    let mut mean: blaf::ColumnVector = vec![0.0; outputs[0].len()];
+
+    for output in outputs.iter() {
+        mean.iter_mut()
+            .zip(output.iter())
+            .for_each(|(mean_elem, elem)| *mean_elem += elem / nb_samples);
+    }
+
+    let mut variance: blaf::ColumnVector = vec![0.0; mean.len()];
+
+    for output in outputs.iter() {
+        variance
+            .iter_mut()
+            .zip(output.iter().zip(mean.iter()))
+            .for_each(|(variance_elem, (elem, mean_elem))| {
+                *variance_elem += (elem - mean_elem).powi(2) / nb_samples
+            });
+    }
+
+    return (mean, variance);
+}
+
+/// Compute weighted inputs of neurons of a layer, weigths*input + bias
+/// Blocked matrix-vector multiplication is used, it gives same result as gemv faster
+fn layer_weighted_inputs(
+    weigths: &blaf::Matrix,
+    input: &blaf::ColumnVector,
+    bias: &blaf::ColumnVector,
+) -> Result<blaf::ColumnVector, NenufarError> {
+    return blaf::gemv_blocked(weigths, input, bias);
+}
+
+/// Pack column vectors of given size as columns of a matrix
+fn columns_to_matrix(
+    columns: &[blaf::ColumnVector],
+    nb_rows: usize,
+) -> Result<blaf::Matrix, String> {
+    let data: Vec<f64> = (0..nb_rows)
+        .flat_map(|row| columns.iter().map(move |column| column[row]))
+        .collect();
+
+    return blaf::Matrix::from_vec(nb_rows, columns.len(), data);
+}
+
+/// Draw a random permutation of indices 0..size by sorting generated numbers
+fn random_permutation<Generator>(size: usize, generator: &Generator) -> Vec<usize>
+where
+    Generator: NumberGenerator,
+{
+    let keys: Vec<f64> = generator.generate_vec(size);
+    let mut permutation: Vec<usize> = (0..size).collect();
+
+    permutation.sort_by(|&a, &b| keys[a].total_cmp(&keys[b]));
+
+    return permutation;
 }
 
 impl NeuralNet {
     /// Construct a neural network from topology
+    /// Weights and bias of each layer are given by generator, which can be any number
+    /// generator or a layer aware one such as Xavier initialization
     pub fn new<Generator>(topology: Topology, random_gen: &Generator) -> Self
     where
-        Generator: NumberGenerator,
+        Generator: LayerAwareGenerator,
     {
         let nb_layer: usize = topology.nb_neurons.len();
 
@@ -26,194 +145,4263 @@ impl NeuralNet {
             let nb_rows: usize = topology.nb_neurons[id + 1];
             let nb_cols: usize = topology.nb_neurons[id];
 
-            weigths.push(blaf::Matrix::new(nb_rows, nb_cols, random_gen));
-            bias.push(random_gen.generate_vec(nb_rows));
+            weigths.push(blaf::Matrix::new_layer(nb_rows, nb_cols, random_gen));
+            bias.push(random_gen.generate_bias(nb_rows));
         }
 
         return Self {
             weigths,
             bias,
             activation_functions: topology.activation_functions,
+            temperature: 1.0,
         };
     }
 
-    /// Predict the output according to input given in argument
-    pub fn predict(&self, input: &blaf::ColumnVector) -> Result<blaf::ColumnVector, String> {
+    /// Construct a neural network from weights and bias of each layer, given in row-major
+    /// order, with a sigmoid activation function on each layer
+    fn sigmoid_network(
+        nb_neurons: &[usize],
+        weigths: Vec<Vec<f64>>,
+        bias: Vec<blaf::ColumnVector>,
+    ) -> Self {
+        return Self {
+            weigths: weigths
+                .into_iter()
+                .enumerate()
+                .map(|(id, data)| {
+                    return blaf::Matrix::from_vec(nb_neurons[id + 1], nb_neurons[id], data)
+                        .unwrap();
+                })
+                .collect(),
+            bias,
+            activation_functions: (1..nb_neurons.len())
+                .map(|_| LayerActivation::Scalar(Box::new(Sigmoid::default())))
+                .collect(),
+            temperature: 1.0,
+        };
+    }
+
+    /// Construct a neural network computing logical AND of two inputs in {0, 1}
+    /// Sigmoid of output neuron is steep enough to behave like a step function
+    pub fn and_network() -> Self {
+        return Self::sigmoid_network(&[2, 1], vec![vec![20.0, 20.0]], vec![vec![-30.0]]);
+    }
+
+    /// Construct a neural network computing logical OR of two inputs in {0, 1}
+    /// Sigmoid of output neuron is steep enough to behave like a step function
+    pub fn or_network() -> Self {
+        return Self::sigmoid_network(&[2, 1], vec![vec![20.0, 20.0]], vec![vec![-10.0]]);
+    }
+
+    /// Construct a neural network computing logical XOR of two inputs in {0, 1}
+    /// Hidden layer computes OR and NAND of inputs, then output neuron computes AND of them
+    pub fn xor_network() -> Self {
+        return Self::sigmoid_network(
+            &[2, 2, 1],
+            vec![vec![20.0, 20.0, -20.0, -20.0], vec![20.0, 20.0]],
+            vec![vec![-10.0, 30.0], vec![-30.0]],
+        );
+    }
+
+    /// Check that size of input is consistent with number of inputs of neural network
+    fn check_input(&self, input: &blaf::ColumnVector) -> Result<(), NenufarError> {
         if input.len() != self.weigths[0].nb_columns() {
-            return Err(String::from(
-                "Number of input are not consistent with topology of neural network",
-            ));
+            return Err(NenufarError::DimensionMismatch {
+                expected: self.weigths[0].nb_columns(),
+                found: input.len(),
+            });
         }
 
-        let max_size: usize = self
-            .bias
-            .iter()
-            .max_by_key(|elem| elem.len())
-            .unwrap()
-            .len();
+        return Ok(());
+    }
 
-        let mut output: blaf::ColumnVector = blaf::ColumnVector::with_capacity(max_size);
-        output.clone_from(&input);
+    /// Compute forward pass according to input given in argument
+    /// The returned pass contains weighted inputs and activations of each layer
+    pub fn forward(&self, input: &blaf::ColumnVector) -> Result<ForwardPass, NenufarError> {
+        self.check_input(input)?;
+
+        let nb_layer: usize = self.weigths.len();
+
+        let mut weighted_inputs: Vec<blaf::ColumnVector> = Vec::with_capacity(nb_layer);
+        let mut activations: Vec<blaf::ColumnVector> = Vec::with_capacity(nb_layer);
+
+        for id in 0..nb_layer {
+            let layer_input: &blaf::ColumnVector = match activations.last() {
+                Some(activation) => activation,
+                None => input,
+            };
 
-        for id in 0..self.weigths.len() {
             let neuron_inputs: blaf::ColumnVector =
-                blaf::gemv(&self.weigths[id], &output, &self.bias[id])?;
+                layer_weighted_inputs(&self.weigths[id], layer_input, &self.bias[id])?;
 
-            output.clone_from(&blaf::apply_activation_function(
-                &self.activation_functions[id],
-                &neuron_inputs,
-            ));
+            activations.push(self.activation_functions[id].activate(&neuron_inputs));
+            weighted_inputs.push(neuron_inputs);
         }
 
-        return Ok(output);
+        return Ok(ForwardPass {
+            weighted_inputs,
+            activations,
+        });
     }
-}
 
-// Unit test
-#[cfg(test)]
-mod tests {
-    use super::super::topology::TopologyBuilder;
-    use super::*;
+    /// Predict the output according to input given in argument
+    pub fn predict(&self, input: &blaf::ColumnVector) -> Result<blaf::ColumnVector, NenufarError> {
+        let mut pass: ForwardPass = self.forward(input)?;
 
-    // Number generator to fill matrix with zero
-    #[derive(Default)]
-    struct ZeroGenerator {}
+        return Ok(pass.activations.pop().unwrap());
+    }
 
-    impl NumberGenerator for ZeroGenerator {
-        fn generate_vec(&self, size: usize) -> Vec<f64> {
-            return vec![0.0; size];
+    /// Predict outputs of a batch of inputs given in argument
+    /// Inputs are packed as columns of a matrix, which is propagated through each layer
+    /// by a matrix-matrix multiplication, so that each weight matrix is read once per layer
+    pub fn predict_batch(
+        &self,
+        inputs: &[blaf::ColumnVector],
+    ) -> Result<Vec<blaf::ColumnVector>, String> {
+        for input in inputs.iter() {
+            self.check_input(input)?;
+        }
+
+        let nb_samples: usize = inputs.len();
+        let mut outputs: Vec<blaf::ColumnVector> = inputs.to_vec();
+
+        for id in 0..self.weigths.len() {
+            let layer_inputs: blaf::Matrix =
+                columns_to_matrix(&outputs, self.weigths[id].nb_columns())?;
+            let products: blaf::Matrix = blaf::gemm(&self.weigths[id], &layer_inputs)?;
+
+            outputs = (0..nb_samples)
+                .map(|sample| {
+                    let neuron_inputs: blaf::ColumnVector = self.bias[id]
+                        .iter()
+                        .enumerate()
+                        .map(|(row, bias)| products[(row, sample)] + bias)
+                        .collect();
+
+                    return self.activation_functions[id].activate(&neuron_inputs);
+                })
+                .collect();
         }
+
+        return Ok(outputs);
     }
 
-    // Activation function for test
-    #[derive(Default)]
-    struct TestActivationFn {}
+    /// Trace the forward pass according to input given in argument
+    /// For each layer, the returned trace contains pre-activation and post-activation vectors
+    pub fn forward_trace(&self, input: &blaf::ColumnVector) -> Result<Vec<LayerTrace>, String> {
+        let pass: ForwardPass = self.forward(input)?;
 
-    impl ActivationFunction for TestActivationFn {
-        fn activate(&self, x: f64) -> f64 {
-            return x;
+        return Ok(pass
+            .weighted_inputs
+            .into_iter()
+            .zip(pass.activations)
+            .map(|(pre_activation, post_activation)| LayerTrace {
+                pre_activation,
+                post_activation,
+            })
+            .collect());
+    }
+
+    /// Backpropagate gradient of a loss with respect to output of neural network
+    /// From traces of forward pass, this function return for each layer the gradient of loss
+    /// with respect to weighted inputs of its neurons (pre-activation).
+    /// Traces can stop before last layer, gradient is then given for output of last traced layer
+    fn backpropagate(
+        &self,
+        traces: &[LayerTrace],
+        output_gradient: &blaf::ColumnVector,
+    ) -> Result<Vec<blaf::ColumnVector>, String> {
+        let nb_layers: usize = traces.len();
+        let mut deltas: Vec<blaf::ColumnVector> = vec![Vec::new(); nb_layers];
+
+        let mut gradient: blaf::ColumnVector = output_gradient.clone();
+
+        for id in (0..nb_layers).rev() {
+            if gradient.len() != traces[id].pre_activation.len() {
+                return Err(String::from(
+                    "Size of gradient is not consistent with topology of neural network",
+                ));
+            }
+
+            deltas[id] =
+                self.activation_functions[id].backward(&traces[id].pre_activation, &gradient);
+
+            if id > 0 {
+                gradient = blaf::gemv_transpose(&self.weigths[id], &deltas[id])?;
+            }
         }
+
+        return Ok(deltas);
     }
 
-    #[test]
-    fn test_neural_net_new() {
-        let nb_neurons: Vec<usize> = vec![2, 3, 1];
+    /// Train neural network with stochastic gradient descent on mean squared error
+    /// Samples are pairs of input and target. For each epoch, weights and bias are updated
+    /// after each sample by backpropagation. This function return mean loss of last epoch
+    pub fn train(
+        &mut self,
+        samples: &[(blaf::ColumnVector, blaf::ColumnVector)],
+        learning_rate: f64,
+        epochs: usize,
+    ) -> Result<f64, String> {
+        if samples.is_empty() {
+            return Err(String::from("There is no sample to train neural network"));
+        }
 
-        let topology: Topology = TopologyBuilder::new()
-            .nb_input(nb_neurons[0])
-            .add_layer(nb_neurons[1], Box::new(TestActivationFn::default()))
-            .add_layer(nb_neurons[2], Box::new(TestActivationFn::default()))
-            .build()
-            .unwrap();
+        let nb_output: usize = self.weigths.last().unwrap().nb_rows();
 
-        let generator: ZeroGenerator = ZeroGenerator::default();
+        if samples.iter().any(|(_, target)| target.len() != nb_output) {
+            return Err(String::from(
+                "Size of target is not consistent with topology of neural network",
+            ));
+        }
 
-        let neural_net: NeuralNet = NeuralNet::new(topology, &generator);
+        let loss: MeanSquaredError = MeanSquaredError::default();
+        let mut epoch_loss: f64 = 0.0;
 
-        let size: usize = nb_neurons.len() - 1;
+        for _ in 0..epochs {
+            epoch_loss = 0.0;
 
-        assert_eq!(neural_net.weigths.len(), size);
-        assert_eq!(neural_net.bias.len(), size);
-        assert_eq!(neural_net.activation_functions.len(), size);
+            for (input, target) in samples.iter() {
+                let traces: Vec<LayerTrace> = self.forward_trace(input)?;
+                let output: &blaf::ColumnVector = &traces.last().unwrap().post_activation;
 
-        for id in 0..size {
-            assert_eq!(neural_net.weigths[id].nb_rows(), nb_neurons[id + 1]);
-            assert_eq!(neural_net.weigths[id].nb_columns(), nb_neurons[id]);
-            assert_eq!(neural_net.bias[id].len(), nb_neurons[id + 1]);
+                epoch_loss += loss.loss(output, target);
+
+                let deltas: Vec<blaf::ColumnVector> =
+                    self.backpropagate(&traces, &loss.gradient(output, target))?;
+
+                for (id, delta) in deltas.iter().enumerate() {
+                    let layer_input: &blaf::ColumnVector = if id == 0 {
+                        input
+                    } else {
+                        &traces[id - 1].post_activation
+                    };
+
+                    self.weigths[id].add_outer_into(delta, layer_input, -learning_rate)?;
+
+                    self.bias[id]
+                        .iter_mut()
+                        .zip(delta.iter())
+                        .for_each(|(bias, value)| *bias -= learning_rate * value);
+                }
+            }
+
+            epoch_loss /= samples.len() as f64;
         }
+
+        return Ok(epoch_loss);
     }
 
-    // Number generator to fill matrix with one
-    #[derive(Default)]
-    struct OneGenerator {}
+    /// Measure how well neural network can memorize random labels of inputs given in argument
+    /// Labels are drawn from a random permutation so that classes are balanced, then network
+    /// is trained on one-hot targets of these labels. Training is done on a clone, so the
+    /// neural network is left unchanged. This function return training accuracy
+    pub fn memorization_accuracy<Generator>(
+        &self,
+        inputs: &[blaf::ColumnVector],
+        learning_rate: f64,
+        epochs: usize,
+        generator: &Generator,
+    ) -> Result<f64, String>
+    where
+        Generator: NumberGenerator,
+    {
+        if inputs.is_empty() {
+            return Err(String::from("There is no input to memorize"));
+        }
 
-    impl NumberGenerator for OneGenerator {
-        fn generate_vec(&self, size: usize) -> Vec<f64> {
-            return vec![1.0; size];
+        let nb_output: usize = self.weigths.last().unwrap().nb_rows();
+
+        let labels: Vec<usize> = random_permutation(inputs.len(), generator)
+            .iter()
+            .map(|index| index % nb_output)
+            .collect();
+
+        let samples: Vec<(blaf::ColumnVector, blaf::ColumnVector)> = inputs
+            .iter()
+            .zip(labels.iter())
+            .map(|(input, &label)| {
+                let mut target: blaf::ColumnVector = vec![0.0; nb_output];
+                target[label] = 1.0;
+                return (input.clone(), target);
+            })
+            .collect();
+
+        let mut neural_net: NeuralNet = self.clone();
+        neural_net.train(&samples, learning_rate, epochs)?;
+
+        let mut nb_correct: usize = 0;
+
+        for (input, &label) in inputs.iter().zip(labels.iter()) {
+            if class_of(&neural_net.predict(input)?) == label {
+                nb_correct += 1;
+            }
         }
+
+        return Ok(nb_correct as f64 / inputs.len() as f64);
     }
 
-    // Function to check if two numbers are approximatively equal
-    fn approx_equal(value: f64, reference: f64, precision: f64) -> bool {
-        let mut error: f64 = (value - reference).abs();
+    /// Compute gradient of a loss with respect to input of neural network
+    fn input_gradient(
+        &self,
+        input: &blaf::ColumnVector,
+        target: &blaf::ColumnVector,
+        loss: &dyn Loss,
+    ) -> Result<blaf::ColumnVector, String> {
+        let traces: Vec<LayerTrace> = self.forward_trace(input)?;
+        let output: &blaf::ColumnVector = &traces.last().unwrap().post_activation;
 
-        if reference != 0.0 {
-            error /= reference.abs();
+        if output.len() != target.len() {
+            return Err(String::from(
+                "Size of target is not consistent with topology of neural network",
+            ));
         }
 
-        return error < precision;
+        let deltas: Vec<blaf::ColumnVector> =
+            self.backpropagate(&traces, &loss.gradient(output, target))?;
+
+        return blaf::gemv_transpose(&self.weigths[0], &deltas[0]);
     }
 
-    #[test]
-    fn test_neural_net_predict_with_perceptron() {
-        let topology: Topology = TopologyBuilder::new()
-            .nb_input(2)
-            .add_layer(1, Box::new(TestActivationFn::default()))
-            .build()
-            .unwrap();
+    /// Compute gradient of entropy of softmax of output with respect to input
+    /// Derivative of entropy H with respect to output y_k is -p_k * (ln(p_k) + H), where p is
+    /// softmax of output, then it is backpropagated through neural network
+    pub fn entropy_gradient(
+        &self,
+        input: &blaf::ColumnVector,
+    ) -> Result<blaf::ColumnVector, String> {
+        let traces: Vec<LayerTrace> = self.forward_trace(input)?;
+        let probabilities: blaf::ColumnVector =
+            blaf::softmax(&traces.last().unwrap().post_activation);
+        let entropy: f64 = blaf::entropy(&probabilities);
 
-        let generator: OneGenerator = OneGenerator::default();
+        let output_gradient: blaf::ColumnVector = probabilities
+            .iter()
+            .map(|p| -p * (p.max(f64::MIN_POSITIVE).ln() + entropy))
+            .collect();
 
-        let neural_net: NeuralNet = NeuralNet::new(topology, &generator);
-        let input: Vec<f64> = vec![1.0, 2.0];
+        let deltas: Vec<blaf::ColumnVector> = self.backpropagate(&traces, &output_gradient)?;
 
-        match neural_net.predict(&input) {
-            Ok(output) => {
-                assert_eq!(output.len(), 1);
-                assert!(approx_equal(
-                    output[0],
-                    input.iter().sum::<f64>() + 1.0,
-                    0.01
-                ))
+        return blaf::gemv_transpose(&self.weigths[0], &deltas[0]);
+    }
+
+    /// Compute Jacobian of output with respect to input, as rows of partial derivatives
+    /// of each output. Each row is computed by backpropagation of a one-hot output gradient
+    fn jacobian(&self, input: &blaf::ColumnVector) -> Result<Vec<blaf::ColumnVector>, String> {
+        let traces: Vec<LayerTrace> = self.forward_trace(input)?;
+        let nb_output: usize = traces.last().unwrap().post_activation.len();
+
+        let mut jacobian: Vec<blaf::ColumnVector> = Vec::with_capacity(nb_output);
+
+        for output_id in 0..nb_output {
+            let mut output_gradient: blaf::ColumnVector = vec![0.0; nb_output];
+            output_gradient[output_id] = 1.0;
+
+            let deltas: Vec<blaf::ColumnVector> = self.backpropagate(&traces, &output_gradient)?;
+            jacobian.push(blaf::gemv_transpose(&self.weigths[0], &deltas[0])?);
+        }
+
+        return Ok(jacobian);
+    }
+
+    /// Compute sparsity pattern of Jacobian of output with respect to input
+    /// Element (i, j) is true when absolute value of partial derivative of output i
+    /// with respect to input j is greater than tolerance given in argument
+    pub fn jacobian_sparsity(
+        &self,
+        input: &blaf::ColumnVector,
+        tolerance: f64,
+    ) -> Result<Vec<Vec<bool>>, String> {
+        return Ok(self
+            .jacobian(input)?
+            .iter()
+            .map(|row| row.iter().map(|x| x.abs() > tolerance).collect())
+            .collect());
+    }
+
+    /// Compute Frobenius norm of Jacobian of output with respect to input
+    /// It measures sensitivity of neural network to input, and can be used as regularization
+    pub fn jacobian_norm(&self, input: &blaf::ColumnVector) -> Result<f64, String> {
+        return Ok(self
+            .jacobian(input)?
+            .iter()
+            .flatten()
+            .map(|x| x * x)
+            .sum::<f64>()
+            .sqrt());
+    }
+
+    /// Find a small perturbation of input which changes its predicted class
+    /// Input is iteratively moved by given step along normalized gradient of loss with respect
+    /// to one-hot target of given class, until predicted class is not this class anymore.
+    /// Input must be predicted as given class, otherwise an error is returned.
+    /// This function return perturbed input and its positive L2 distance to original input
+    pub fn minimal_adversarial_perturbation(
+        &self,
+        input: &blaf::ColumnVector,
+        target_class: usize,
+        max_iters: usize,
+        step: f64,
+        loss: &dyn Loss,
+    ) -> Result<(blaf::ColumnVector, f64), String> {
+        let nb_output: usize = self.weigths.last().unwrap().nb_rows();
+
+        if target_class >= nb_output {
+            return Err(format!(
+                "Class {} does not exist, neural network has {} outputs",
+                target_class, nb_output
+            ));
+        }
+
+        if class_of(&self.predict(input)?) != target_class {
+            return Err(format!("Input is not predicted as class {}", target_class));
+        }
+
+        let mut target: blaf::ColumnVector = vec![0.0; nb_output];
+        target[target_class] = 1.0;
+
+        let mut perturbed: blaf::ColumnVector = input.clone();
+
+        for _ in 0..=max_iters {
+            if class_of(&self.predict(&perturbed)?) != target_class {
+                let distance: f64 = perturbed
+                    .iter()
+                    .zip(input.iter())
+                    .map(|(a, b)| (a - b).powi(2))
+                    .sum::<f64>()
+                    .sqrt();
+
+                return Ok((perturbed, distance));
             }
-            Err(_) => assert!(false),
+
+            let gradient: blaf::ColumnVector = self.input_gradient(&perturbed, &target, loss)?;
+            let norm: f64 = gradient.iter().map(|elem| elem * elem).sum::<f64>().sqrt();
+
+            if norm == 0.0 {
+                break;
+            }
+
+            perturbed
+                .iter_mut()
+                .zip(gradient.iter())
+                .for_each(|(value, elem)| *value += step * elem / norm);
         }
+
+        return Err(String::from(
+            "No perturbation changing predicted class has been found",
+        ));
     }
 
-    #[test]
-    fn test_neural_net_predict_with_simple_topology() {
-        let topology: Topology = TopologyBuilder::new()
-            .nb_input(2)
-            .add_layer(2, Box::new(TestActivationFn::default()))
-            .add_layer(1, Box::new(TestActivationFn::default()))
-            .build()
-            .unwrap();
+    /// Compute importance of each neuron of each layer over a dataset
+    /// Importance of a neuron is its absolute gradient of loss with respect to its output,
+    /// accumulated over inputs and targets given in argument
+    pub fn neuron_importance(
+        &self,
+        inputs: &[blaf::ColumnVector],
+        targets: &[blaf::ColumnVector],
+        loss: &dyn Loss,
+    ) -> Result<Vec<blaf::ColumnVector>, String> {
+        if inputs.len() != targets.len() {
+            return Err(String::from("Number of inputs and targets must be equal"));
+        }
 
-        let generator: OneGenerator = OneGenerator::default();
+        let mut importances: Vec<blaf::ColumnVector> = self
+            .weigths
+            .iter()
+            .map(|weigths| vec![0.0; weigths.nb_rows()])
+            .collect();
 
-        let neural_net: NeuralNet = NeuralNet::new(topology, &generator);
-        let input: Vec<f64> = vec![1.0, 2.0];
+        for (input, target) in inputs.iter().zip(targets.iter()) {
+            let traces: Vec<LayerTrace> = self.forward_trace(input)?;
+            let output: &blaf::ColumnVector = &traces.last().unwrap().post_activation;
 
-        match neural_net.predict(&input) {
-            Ok(output) => {
-                assert_eq!(output.len(), 1);
-                assert!(approx_equal(
-                    output[0],
-                    (input.iter().sum::<f64>() + 1.0) * 2.0 + 1.0,
-                    0.01
-                ))
+            if output.len() != target.len() {
+                return Err(String::from(
+                    "Size of target is not consistent with topology of neural network",
+                ));
+            }
+
+            let output_gradient: blaf::ColumnVector = loss.gradient(output, target);
+            let deltas: Vec<blaf::ColumnVector> = self.backpropagate(&traces, &output_gradient)?;
+
+            for (id, importance) in importances.iter_mut().enumerate() {
+                // Gradient with respect to outputs of neurons of this layer
+                let gradient: blaf::ColumnVector = if id + 1 < deltas.len() {
+                    blaf::gemv_transpose(&self.weigths[id + 1], &deltas[id + 1])?
+                } else {
+                    output_gradient.clone()
+                };
+
+                importance
+                    .iter_mut()
+                    .zip(gradient.iter())
+                    .for_each(|(value, elem)| *value += elem.abs());
             }
-            Err(_) => assert!(false),
         }
+
+        return Ok(importances);
     }
 
-    #[test]
-    fn test_neural_net_predict_should_return_error() {
-        let topology: Topology = TopologyBuilder::new()
-            .nb_input(2)
-            .add_layer(2, Box::new(TestActivationFn::default()))
-            .add_layer(1, Box::new(TestActivationFn::default()))
-            .build()
-            .unwrap();
+    /// Compute gradients of a loss with respect to weights and bias of each layer
+    /// for input and target given in argument
+    fn parameter_gradients(
+        &self,
+        input: &blaf::ColumnVector,
+        target: &blaf::ColumnVector,
+        loss: &dyn Loss,
+    ) -> Result<(Vec<blaf::Matrix>, Vec<blaf::ColumnVector>), String> {
+        let traces: Vec<LayerTrace> = self.forward_trace(input)?;
+        let output: &blaf::ColumnVector = &traces.last().unwrap().post_activation;
 
-        let generator: OneGenerator = OneGenerator::default();
+        if output.len() != target.len() {
+            return Err(String::from(
+                "Size of target is not consistent with topology of neural network",
+            ));
+        }
 
-        let neural_net: NeuralNet = NeuralNet::new(topology, &generator);
-        let input: Vec<f64> = vec![1.0, 2.0, 3.0];
+        let deltas: Vec<blaf::ColumnVector> =
+            self.backpropagate(&traces, &loss.gradient(output, target))?;
 
-        match neural_net.predict(&input) {
-            Ok(_) => assert!(false),
-            Err(_) => (),
+        let mut weight_grads: Vec<blaf::Matrix> = Vec::with_capacity(deltas.len());
+
+        for (id, delta) in deltas.iter().enumerate() {
+            let layer_input: &blaf::ColumnVector = if id == 0 {
+                input
+            } else {
+                &traces[id - 1].post_activation
+            };
+
+            let data: Vec<f64> = delta
+                .iter()
+                .flat_map(|d| layer_input.iter().map(move |x| d * x))
+                .collect();
+
+            weight_grads.push(blaf::Matrix::from_vec(
+                delta.len(),
+                layer_input.len(),
+                data,
+            )?);
+        }
+
+        return Ok((weight_grads, deltas));
+    }
+
+    /// Compute mean gradient signal-to-noise ratio of parameters over a batch
+    /// For each parameter, ratio is absolute value of mean of its gradients over samples
+    /// divided by their standard deviation. A parameter whose gradients are all equal has no
+    /// noise and an infinite ratio, which would swamp the mean, so it is skipped.
+    /// This function return mean ratio of other parameters, and an error if every parameter
+    /// is skipped
+    pub fn gradient_snr(
+        &self,
+        inputs: &[blaf::ColumnVector],
+        targets: &[blaf::ColumnVector],
+        loss: &dyn Loss,
+    ) -> Result<f64, String> {
+        if inputs.len() != targets.len() {
+            return Err(String::from("Number of inputs and targets must be equal"));
+        }
+
+        if inputs.is_empty() {
+            return Err(String::from("There is no sample to compute gradients"));
+        }
+
+        let mut gradients: Vec<blaf::ColumnVector> = Vec::with_capacity(inputs.len());
+
+        for (input, target) in inputs.iter().zip(targets.iter()) {
+            let (weight_grads, bias_grads) = self.parameter_gradients(input, target, loss)?;
+            gradients.push(blaf::flatten(&weight_grads, &bias_grads));
+        }
+
+        let (means, variances) = output_statistics(&gradients);
+
+        // Standard deviation below rounding error of mean is considered as zero
+        let ratios: Vec<f64> = means
+            .iter()
+            .zip(variances.iter())
+            .filter(|(mean, variance)| variance.sqrt() > f64::EPSILON * mean.abs())
+            .map(|(mean, variance)| mean.abs() / variance.sqrt())
+            .collect();
+
+        if ratios.is_empty() {
+            return Err(String::from(
+                "Gradients of every parameter are constant over samples",
+            ));
         }
+
+        return Ok(ratios.iter().sum::<f64>() / ratios.len() as f64);
+    }
+
+    /// Compute relevance of each input for an output with epsilon-rule of layer-wise relevance
+    /// propagation. Relevance of output is its value, then relevance of a neuron input is
+    /// redistributed to inputs of layer proportionally to their contribution input * weight
+    /// to weighted input of neuron, which is stabilised by epsilon given in argument
+    pub fn lrp(
+        &self,
+        input: &blaf::ColumnVector,
+        output_index: usize,
+        epsilon: f64,
+    ) -> Result<blaf::ColumnVector, String> {
+        let traces: Vec<LayerTrace> = self.forward_trace(input)?;
+        let output: &blaf::ColumnVector = &traces.last().unwrap().post_activation;
+
+        if output_index >= output.len() {
+            return Err(format!(
+                "Output {} does not exist, neural network has {} outputs",
+                output_index,
+                output.len()
+            ));
+        }
+
+        let mut relevance: blaf::ColumnVector = vec![0.0; output.len()];
+        relevance[output_index] = output[output_index];
+
+        for id in (0..self.weigths.len()).rev() {
+            let layer_input: &blaf::ColumnVector = if id == 0 {
+                input
+            } else {
+                &traces[id - 1].post_activation
+            };
+
+            let scaled: blaf::ColumnVector = relevance
+                .iter()
+                .zip(traces[id].pre_activation.iter())
+                .map(|(r, z)| {
+                    let stabiliser: f64 = if *z >= 0.0 { epsilon } else { -epsilon };
+                    return r / (z + stabiliser);
+                })
+                .collect();
+
+            relevance = blaf::gemv_transpose(&self.weigths[id], &scaled)?
+                .iter()
+                .zip(layer_input.iter())
+                .map(|(c, a)| a * c)
+                .collect();
+        }
+
+        return Ok(relevance);
+    }
+
+    /// Predict the output according to input given in argument, checking that absolute value
+    /// of every intermediate activation does not exceed given bound
+    pub fn predict_with_activation_bounds(
+        &self,
+        input: &blaf::ColumnVector,
+        max_abs: f64,
+    ) -> Result<blaf::ColumnVector, String> {
+        let traces: Vec<LayerTrace> = self.forward_trace(input)?;
+
+        for (id, trace) in traces.iter().enumerate() {
+            if let Some(value) = trace
+                .post_activation
+                .iter()
+                .find(|value| value.abs() > max_abs || value.is_nan())
+            {
+                return Err(format!(
+                    "Activation {} in layer {} exceeds bound {} in absolute value",
+                    value, id, max_abs
+                ));
+            }
+        }
+
+        return Ok(traces.last().unwrap().post_activation.clone());
+    }
+
+    /// Fit temperature used to calibrate outputs of neural network
+    /// Temperature minimizing cross-entropy between softmax of logits divided by temperature
+    /// and targets is found by golden-section search, then stored for calibrated prediction
+    pub fn fit_temperature(
+        &mut self,
+        logits: &[blaf::ColumnVector],
+        targets: &[blaf::ColumnVector],
+    ) -> Result<f64, String> {
+        if logits.is_empty() {
+            return Err(String::from(
+                "There is no logits to fit temperature of neural network",
+            ));
+        }
+
+        if logits.len() != targets.len() {
+            return Err(String::from("Number of logits and targets must be equal"));
+        }
+
+        if logits
+            .iter()
+            .zip(targets.iter())
+            .any(|(logit, target)| logit.len() != target.len())
+        {
+            return Err(String::from("Size of each logit and target must be equal"));
+        }
+
+        // Golden-section search on logarithm of temperature
+        let ratio: f64 = (5.0_f64.sqrt() - 1.0) / 2.0;
+        let loss = |log_temperature: f64| -> f64 {
+            return temperature_cross_entropy(logits, targets, log_temperature.exp());
+        };
+
+        let mut lower: f64 = 0.01_f64.ln();
+        let mut upper: f64 = 100.0_f64.ln();
+
+        let mut left: f64 = upper - ratio * (upper - lower);
+        let mut right: f64 = lower + ratio * (upper - lower);
+        let mut left_loss: f64 = loss(left);
+        let mut right_loss: f64 = loss(right);
+
+        // Retained interior point becomes an interior point of the reduced interval,
+        // so that a single new loss is computed at each iteration
+        for _ in 0..100 {
+            if left_loss < right_loss {
+                upper = right;
+                right = left;
+                right_loss = left_loss;
+                left = upper - ratio * (upper - lower);
+                left_loss = loss(left);
+            } else {
+                lower = left;
+                left = right;
+                left_loss = right_loss;
+                right = lower + ratio * (upper - lower);
+                right_loss = loss(right);
+            }
+        }
+
+        self.temperature = ((lower + upper) / 2.0).exp();
+
+        return Ok(self.temperature);
+    }
+
+    /// Predict the calibrated class probabilities according to input given in argument
+    /// Output of neural network is divided by fitted temperature before applying softmax
+    pub fn predict_calibrated(
+        &self,
+        input: &blaf::ColumnVector,
+    ) -> Result<blaf::ColumnVector, String> {
+        let output: blaf::ColumnVector = self.predict(input)?;
+        let scaled: blaf::ColumnVector = output.iter().map(|x| x / self.temperature).collect();
+
+        return Ok(blaf::softmax(&scaled));
+    }
+
+    /// Predict class of input or abstain when neural network is not confident enough
+    /// Softmax is applied on output, and the most probable class is returned only if its
+    /// probability exceeds confidence threshold given in argument, otherwise None is returned
+    pub fn predict_or_abstain(
+        &self,
+        input: &blaf::ColumnVector,
+        confidence_threshold: f64,
+    ) -> Result<Option<usize>, String> {
+        let probabilities: blaf::ColumnVector = blaf::softmax(&self.predict(input)?);
+        let class: usize = class_of(&probabilities);
+
+        if probabilities[class] > confidence_threshold {
+            return Ok(Some(class));
+        }
+
+        return Ok(None);
+    }
+
+    /// Predict smoothed class distribution of input under Gaussian noise
+    /// Softmax of output is averaged over given number of noisy copies of input, where noise
+    /// of each feature is noise standard deviation times the number drawn for it by generator.
+    /// Generator is expected to give numbers with standard normal distribution
+    pub fn smoothed_predict<Generator>(
+        &self,
+        input: &blaf::ColumnVector,
+        noise_std: f64,
+        samples: usize,
+        generator: &Generator,
+    ) -> Result<blaf::ColumnVector, String>
+    where
+        Generator: NumberGenerator,
+    {
+        if samples == 0 {
+            return Err(String::from("Number of samples must be positive"));
+        }
+
+        let mut distribution: blaf::ColumnVector = Vec::new();
+
+        for _ in 0..samples {
+            let noisy_input: blaf::ColumnVector = input
+                .iter()
+                .zip(generator.generate_vec(input.len()).iter())
+                .map(|(value, draw)| value + noise_std * draw)
+                .collect();
+
+            let probabilities: blaf::ColumnVector = blaf::softmax(&self.predict(&noisy_input)?);
+
+            if distribution.is_empty() {
+                distribution = vec![0.0; probabilities.len()];
+            }
+
+            distribution
+                .iter_mut()
+                .zip(probabilities.iter())
+                .for_each(|(value, p)| *value += p / samples as f64);
+        }
+
+        return Ok(distribution);
+    }
+
+    /// Compute predictive entropy of each input given in argument
+    /// Softmax is applied on output of neural network to get a probability distribution
+    pub fn batch_entropy(&self, inputs: &[blaf::ColumnVector]) -> Result<Vec<f64>, String> {
+        return inputs
+            .iter()
+            .map(|input| Ok(blaf::entropy(&blaf::softmax(&self.predict(input)?))))
+            .collect();
+    }
+
+    /// Prune neurons of a hidden layer by magnitude
+    /// Neurons with lowest L2 norm of incoming weights are removed, keeping the given fraction
+    /// of neurons in layer. Weights of removed neurons are dropped in this layer and in next one.
+    /// This function return number of removed neurons
+    pub fn prune_neurons(&mut self, layer: usize, keep_fraction: f64) -> Result<usize, String> {
+        if layer + 1 >= self.weigths.len() {
+            return Err(String::from(
+                "Only neurons of a hidden layer of neural network can be pruned",
+            ));
+        }
+
+        if keep_fraction <= 0.0 || keep_fraction > 1.0 {
+            return Err(String::from("Fraction of kept neurons must be in (0, 1]"));
+        }
+
+        let nb_neurons: usize = self.weigths[layer].nb_rows();
+        let nb_kept: usize = ((keep_fraction * nb_neurons as f64).ceil() as usize).max(1);
+
+        let norms: Vec<f64> = (0..nb_neurons)
+            .map(|row| {
+                return self.weigths[layer]
+                    .row(row)
+                    .iter()
+                    .map(|weigth| weigth * weigth)
+                    .sum::<f64>()
+                    .sqrt();
+            })
+            .collect();
+
+        let mut kept: Vec<usize> = (0..nb_neurons).collect();
+        kept.sort_by(|&a, &b| norms[b].total_cmp(&norms[a]));
+        kept.truncate(nb_kept);
+        kept.sort();
+
+        self.weigths[layer] = self.weigths[layer].select_rows(&kept);
+        self.bias[layer] = kept.iter().map(|&row| self.bias[layer][row]).collect();
+        self.weigths[layer + 1] = self.weigths[layer + 1].select_columns(&kept);
+
+        return Ok(nb_neurons - nb_kept);
+    }
+
+    /// Replace activation function applied on each neuron of a layer
+    pub fn set_activation(
+        &mut self,
+        layer: usize,
+        activation: Box<dyn ActivationFunction>,
+    ) -> Result<(), String> {
+        if layer >= self.activation_functions.len() {
+            return Err(format!(
+                "Layer {} does not exist, neural network has {} layers",
+                layer,
+                self.activation_functions.len()
+            ));
+        }
+
+        self.activation_functions[layer] = LayerActivation::Scalar(activation);
+
+        return Ok(());
+    }
+
+    /// Compute embedding of input given in argument, i.e. output of neurons of a layer
+    /// Embedding of last layer is the prediction of neural network. Embeddings of earlier
+    /// layers are the building block for representation learning, such as contrastive setups
+    pub fn embed(
+        &self,
+        input: &blaf::ColumnVector,
+        embedding_layer: usize,
+    ) -> Result<blaf::ColumnVector, String> {
+        if embedding_layer >= self.weigths.len() {
+            return Err(format!(
+                "Layer {} does not exist, neural network has {} layers",
+                embedding_layer,
+                self.weigths.len()
+            ));
+        }
+
+        let mut pass: ForwardPass = self.forward(input)?;
+
+        return Ok(pass.activations.swap_remove(embedding_layer));
+    }
+
+    /// Reconstruct an input whose embedding at given layer matches target embedding
+    /// Starting from an input drawn by generator, input is optimized by gradient descent
+    /// on mean squared error between its embedding and target embedding
+    pub fn invert_embedding<Generator>(
+        &self,
+        target_embedding: &blaf::ColumnVector,
+        layer: usize,
+        steps: usize,
+        lr: f64,
+        generator: &Generator,
+    ) -> Result<blaf::ColumnVector, String>
+    where
+        Generator: NumberGenerator,
+    {
+        if layer >= self.weigths.len() {
+            return Err(format!(
+                "Layer {} does not exist, neural network has {} layers",
+                layer,
+                self.weigths.len()
+            ));
+        }
+
+        if target_embedding.len() != self.weigths[layer].nb_rows() {
+            return Err(String::from(
+                "Size of target embedding is not consistent with topology of neural network",
+            ));
+        }
+
+        let loss: MeanSquaredError = MeanSquaredError::default();
+        let mut input: blaf::ColumnVector = generator.generate_vec(self.weigths[0].nb_columns());
+
+        for _ in 0..steps {
+            let mut traces: Vec<LayerTrace> = self.forward_trace(&input)?;
+            traces.truncate(layer + 1);
+
+            let embedding: &blaf::ColumnVector = &traces[layer].post_activation;
+
+            let deltas: Vec<blaf::ColumnVector> =
+                self.backpropagate(&traces, &loss.gradient(embedding, target_embedding))?;
+
+            let gradient: blaf::ColumnVector = blaf::gemv_transpose(&self.weigths[0], &deltas[0])?;
+
+            input
+                .iter_mut()
+                .zip(gradient.iter())
+                .for_each(|(x, g)| *x -= lr * g);
+        }
+
+        return Ok(input);
+    }
+
+    /// Compute mean loss of neural network over inputs and targets given in argument
+    fn mean_loss(
+        &self,
+        inputs: &[blaf::ColumnVector],
+        targets: &[blaf::ColumnVector],
+        loss: &dyn Loss,
+    ) -> Result<f64, String> {
+        if inputs.is_empty() || inputs.len() != targets.len() {
+            return Err(String::from(
+                "Number of inputs and targets must be equal and non zero",
+            ));
+        }
+
+        let mut total: f64 = 0.0;
+
+        for (input, target) in inputs.iter().zip(targets.iter()) {
+            let output: blaf::ColumnVector = self.predict(input)?;
+
+            if output.len() != target.len() {
+                return Err(String::from(
+                    "Size of target is not consistent with topology of neural network",
+                ));
+            }
+
+            total += loss.loss(&output, target);
+        }
+
+        return Ok(total / inputs.len() as f64);
+    }
+
+    /// Compute loss increase when a layer is ablated
+    /// Ablated layer is replaced by identity when its numbers of inputs and outputs are
+    /// equal, otherwise its outputs are zeroed. This function return difference between
+    /// mean loss over dataset of ablated neural network and the one of neural network
+    pub fn layer_ablation(
+        &self,
+        inputs: &[blaf::ColumnVector],
+        targets: &[blaf::ColumnVector],
+        loss: &dyn Loss,
+        layer: usize,
+    ) -> Result<f64, String> {
+        if layer >= self.weigths.len() {
+            return Err(format!(
+                "Layer {} does not exist, neural network has {} layers",
+                layer,
+                self.weigths.len()
+            ));
+        }
+
+        let baseline: f64 = self.mean_loss(inputs, targets, loss)?;
+        let is_identity: bool = self.weigths[layer].nb_rows() == self.weigths[layer].nb_columns();
+
+        let mut total: f64 = 0.0;
+
+        for (input, target) in inputs.iter().zip(targets.iter()) {
+            let mut output: blaf::ColumnVector = input.clone();
+
+            for id in 0..self.weigths.len() {
+                if id == layer {
+                    if !is_identity {
+                        output = vec![0.0; self.weigths[id].nb_rows()];
+                    }
+
+                    continue;
+                }
+
+                let neuron_inputs: blaf::ColumnVector =
+                    layer_weighted_inputs(&self.weigths[id], &output, &self.bias[id])?;
+
+                output = self.activation_functions[id].activate(&neuron_inputs);
+            }
+
+            total += loss.loss(&output, target);
+        }
+
+        return Ok(total / inputs.len() as f64 - baseline);
+    }
+
+    /// Compute partial dependence of output of neural network on a feature
+    /// For each value of grid, the feature is set to this value for all inputs given in
+    /// argument, then output is averaged over inputs. Neural network must have a single output
+    pub fn partial_dependence(
+        &self,
+        inputs: &[blaf::ColumnVector],
+        feature: usize,
+        grid: &[f64],
+    ) -> Result<Vec<f64>, String> {
+        if self.weigths.last().unwrap().nb_rows() != 1 {
+            return Err(String::from(
+                "Partial dependence is defined for neural network with a single output",
+            ));
+        }
+
+        if feature >= self.weigths[0].nb_columns() {
+            return Err(format!(
+                "Feature {} does not exist, neural network has {} inputs",
+                feature,
+                self.weigths[0].nb_columns()
+            ));
+        }
+
+        if inputs.is_empty() {
+            return Err(String::from(
+                "There is no input to compute partial dependence",
+            ));
+        }
+
+        let mut curve: Vec<f64> = Vec::with_capacity(grid.len());
+
+        for value in grid.iter() {
+            let mut total: f64 = 0.0;
+
+            for input in inputs.iter() {
+                let mut modified_input: blaf::ColumnVector = input.clone();
+                modified_input[feature] = *value;
+
+                total += self.predict(&modified_input)?[0];
+            }
+
+            curve.push(total / inputs.len() as f64);
+        }
+
+        return Ok(curve);
+    }
+
+    /// Compute permutation importance of each input feature
+    /// For each feature, values of this feature are shuffled across samples and
+    /// importance is the resulting increase of mean loss
+    pub fn permutation_importance<Generator>(
+        &self,
+        inputs: &[blaf::ColumnVector],
+        targets: &[blaf::ColumnVector],
+        loss: &dyn Loss,
+        generator: &Generator,
+    ) -> Result<blaf::ColumnVector, String>
+    where
+        Generator: NumberGenerator,
+    {
+        let baseline: f64 = self.mean_loss(inputs, targets, loss)?;
+        let nb_features: usize = self.weigths[0].nb_columns();
+
+        let mut importances: blaf::ColumnVector = blaf::ColumnVector::with_capacity(nb_features);
+
+        for feature in 0..nb_features {
+            let permutation: Vec<usize> = random_permutation(inputs.len(), generator);
+            let mut permuted_inputs: Vec<blaf::ColumnVector> = inputs.to_vec();
+
+            permuted_inputs
+                .iter_mut()
+                .zip(permutation.iter())
+                .for_each(|(input, &index)| input[feature] = inputs[index][feature]);
+
+            importances.push(self.mean_loss(&permuted_inputs, targets, loss)? - baseline);
+        }
+
+        return Ok(importances);
+    }
+
+    /// Compute outputs of every layer with a fixed dropout mask per layer
+    /// Outputs of neurons whose mask is false are zeroed, and the other ones are scaled by
+    /// inverse of kept fraction of the layer. An all true mask gives a normal forward pass
+    pub fn forward_with_mask(
+        &self,
+        input: &blaf::ColumnVector,
+        masks: &[Vec<bool>],
+    ) -> Result<Vec<blaf::ColumnVector>, String> {
+        if masks.len() != self.weigths.len() {
+            return Err(String::from(
+                "Number of masks must be equal to number of layers of neural network",
+            ));
+        }
+
+        self.check_input(input)?;
+
+        let mut outputs: Vec<blaf::ColumnVector> = Vec::with_capacity(self.weigths.len());
+
+        for (id, mask) in masks.iter().enumerate() {
+            if mask.len() != self.weigths[id].nb_rows() {
+                return Err(format!(
+                    "Size of mask of layer {} is not consistent with its number of neurons",
+                    id
+                ));
+            }
+
+            let layer_input: &blaf::ColumnVector = outputs.last().unwrap_or(input);
+
+            let neuron_inputs: blaf::ColumnVector =
+                layer_weighted_inputs(&self.weigths[id], layer_input, &self.bias[id])?;
+
+            let nb_kept: usize = mask.iter().filter(|&&keep| keep).count();
+            let scale: f64 = if nb_kept > 0 {
+                mask.len() as f64 / nb_kept as f64
+            } else {
+                0.0
+            };
+
+            let output: blaf::ColumnVector = self.activation_functions[id]
+                .activate(&neuron_inputs)
+                .iter()
+                .zip(mask.iter())
+                .map(|(&value, &keep)| if keep { scale * value } else { 0.0 })
+                .collect();
+
+            outputs.push(output);
+        }
+
+        return Ok(outputs);
+    }
+
+    /// Predict the output for a one-hot probe input, having given magnitude at given index
+    /// and zeros elsewhere. It reveals the isolated effect of each input feature
+    pub fn probe_input(&self, index: usize, magnitude: f64) -> Result<blaf::ColumnVector, String> {
+        let nb_input: usize = self.weigths[0].nb_columns();
+
+        if index >= nb_input {
+            return Err(format!(
+                "Input {} does not exist, neural network has {} inputs",
+                index, nb_input
+            ));
+        }
+
+        let mut input: blaf::ColumnVector = vec![0.0; nb_input];
+        input[index] = magnitude;
+
+        return Ok(self.predict(&input)?);
+    }
+
+    /// Get layer dimensions of neural network, including input layer
+    fn layer_dimensions(&self) -> Vec<usize> {
+        let mut nb_neurons: Vec<usize> = Vec::with_capacity(self.weigths.len() + 1);
+
+        nb_neurons.push(self.weigths[0].nb_columns());
+        nb_neurons.extend(self.weigths.iter().map(|weigths| weigths.nb_rows()));
+
+        return nb_neurons;
+    }
+
+    /// Get parameter state of neural network
+    pub fn state(&self) -> NetworkState {
+        return NetworkState {
+            nb_neurons: self.layer_dimensions(),
+            weigths: self
+                .weigths
+                .iter()
+                .map(|weigths| weigths.data().to_vec())
+                .collect(),
+            bias: self.bias.clone(),
+            temperature: self.temperature,
+        };
+    }
+
+    /// Load parameter state in neural network
+    /// State must come from a neural network with same layer dimensions
+    pub fn load_state(&mut self, state: NetworkState) -> Result<(), String> {
+        let nb_neurons: Vec<usize> = self.layer_dimensions();
+
+        if state.nb_neurons != nb_neurons {
+            return Err(String::from(
+                "Layer dimensions of state are not consistent with topology of neural network",
+            ));
+        }
+
+        if state.weigths.len() != self.weigths.len() || state.bias.len() != self.bias.len() {
+            return Err(String::from(
+                "Number of layers of state is not consistent with topology of neural network",
+            ));
+        }
+
+        let mut weigths: Vec<blaf::Matrix> = Vec::with_capacity(state.weigths.len());
+
+        for (id, (data, bias)) in state.weigths.into_iter().zip(state.bias.iter()).enumerate() {
+            if bias.len() != nb_neurons[id + 1] {
+                return Err(format!(
+                    "Size of bias of layer {} is not consistent with its number of neurons",
+                    id
+                ));
+            }
+
+            weigths.push(blaf::Matrix::from_vec(
+                nb_neurons[id + 1],
+                nb_neurons[id],
+                data,
+            )?);
+        }
+
+        self.weigths = weigths;
+        self.bias = state.bias;
+        self.temperature = state.temperature;
+
+        return Ok(());
+    }
+
+    /// Get parameters of neural network as a flat vector
+    /// Weights of each layer in row-major order come first, then bias of each layer
+    pub fn parameters(&self) -> blaf::ColumnVector {
+        return blaf::flatten(&self.weigths, &self.bias);
+    }
+
+    /// Set parameters of neural network from a flat vector ordered as given by parameters
+    pub fn set_parameters(&mut self, parameters: &[f64]) -> Result<(), String> {
+        let nb_parameters: usize = self.weigths.iter().map(|w| w.data().len()).sum::<usize>()
+            + self.bias.iter().map(|b| b.len()).sum::<usize>();
+
+        if parameters.len() != nb_parameters {
+            return Err(String::from(
+                "Number of parameters is not consistent with topology of neural network",
+            ));
+        }
+
+        let mut offset: usize = 0;
+
+        for weigths in self.weigths.iter_mut() {
+            let size: usize = weigths.data().len();
+
+            *weigths = blaf::Matrix::from_vec(
+                weigths.nb_rows(),
+                weigths.nb_columns(),
+                parameters[offset..(offset + size)].to_vec(),
+            )?;
+            offset += size;
+        }
+
+        for bias in self.bias.iter_mut() {
+            let size: usize = bias.len();
+
+            bias.copy_from_slice(&parameters[offset..(offset + size)]);
+            offset += size;
+        }
+
+        return Ok(());
+    }
+
+    /// Apply flat gradients computed asynchronously against given version of parameters
+    /// Gradients are ordered as given by parameters, and applier keeps version of parameters
+    /// of this neural network, so that stale gradients are down-weighted.
+    /// This function return staleness of applied gradients
+    pub fn apply_stale_gradients(
+        &mut self,
+        applier: &mut StaleGradientApplier,
+        gradients: &[f64],
+        gradient_version: usize,
+    ) -> Result<usize, String> {
+        let mut parameters: blaf::ColumnVector = self.parameters();
+        let staleness: usize = applier.apply(&mut parameters, gradients, gradient_version)?;
+
+        self.set_parameters(&parameters)?;
+
+        return Ok(staleness);
+    }
+
+    /// Compute points of decision boundary of a neural network with two inputs and one output
+    /// Output is evaluated on a regular grid of resolution x resolution points, and for each
+    /// pair of neighbouring grid points where output crosses threshold, the crossing point is
+    /// located by linear interpolation
+    pub fn decision_boundary_2d(
+        &self,
+        x_range: (f64, f64),
+        y_range: (f64, f64),
+        resolution: usize,
+        threshold: f64,
+    ) -> Result<Vec<(f64, f64)>, String> {
+        if self.weigths[0].nb_columns() != 2 || self.weigths.last().unwrap().nb_rows() != 1 {
+            return Err(String::from(
+                "Decision boundary needs a neural network with two inputs and one output",
+            ));
+        }
+
+        if resolution < 2 {
+            return Err(String::from("Resolution of grid must be at least two"));
+        }
+
+        let step_x: f64 = (x_range.1 - x_range.0) / (resolution - 1) as f64;
+        let step_y: f64 = (y_range.1 - y_range.0) / (resolution - 1) as f64;
+
+        let mut grid: Vec<f64> = Vec::with_capacity(resolution * resolution);
+
+        for i in 0..resolution {
+            for j in 0..resolution {
+                let x: f64 = x_range.0 + i as f64 * step_x;
+                let y: f64 = y_range.0 + j as f64 * step_y;
+
+                grid.push(self.predict(&vec![x, y])?[0] - threshold);
+            }
+        }
+
+        let mut points: Vec<(f64, f64)> = Vec::new();
+
+        // Crossing between two grid points (i, j) and (i + di, j + dj)
+        let mut add_crossing = |i: usize, j: usize, di: usize, dj: usize| {
+            let value: f64 = grid[i * resolution + j];
+            let next_value: f64 = grid[(i + di) * resolution + j + dj];
+
+            if (value <= 0.0) != (next_value <= 0.0) {
+                let ratio: f64 = value / (value - next_value);
+
+                points.push((
+                    x_range.0 + (i as f64 + ratio * di as f64) * step_x,
+                    y_range.0 + (j as f64 + ratio * dj as f64) * step_y,
+                ));
+            }
+        };
+
+        for i in 0..resolution {
+            for j in 0..resolution {
+                if i + 1 < resolution {
+                    add_crossing(i, j, 1, 0);
+                }
+
+                if j + 1 < resolution {
+                    add_crossing(i, j, 0, 1);
+                }
+            }
+        }
+
+        return Ok(points);
+    }
+
+    /// Write outputs of each layer for input given in argument as CSV
+    /// There is one row per layer, made of comma-separated outputs of its neurons
+    pub fn activations_to_csv(
+        &self,
+        input: &blaf::ColumnVector,
+        writer: &mut impl io::Write,
+    ) -> io::Result<()> {
+        let traces: Vec<LayerTrace> = self
+            .forward_trace(input)
+            .map_err(|message| io::Error::new(io::ErrorKind::InvalidInput, message))?;
+
+        for trace in traces.iter() {
+            let row: Vec<String> = trace
+                .post_activation
+                .iter()
+                .map(|value| value.to_string())
+                .collect();
+
+            writeln!(writer, "{}", row.join(","))?;
+        }
+
+        return Ok(());
+    }
+
+    /// Predict the output according to input given in argument, simulating fixed-point
+    /// arithmetic: weights, bias and every intermediate vector are rounded to nearest
+    /// multiple of 2^(-fractional_bits)
+    pub fn predict_fixed_point(
+        &self,
+        input: &blaf::ColumnVector,
+        fractional_bits: usize,
+    ) -> Result<blaf::ColumnVector, String> {
+        self.check_input(input)?;
+
+        let scale: f64 = 2.0_f64.powi(fractional_bits as i32);
+        let round = |value: &f64| -> f64 { (value * scale).round() / scale };
+
+        let mut output: blaf::ColumnVector = input.iter().map(round).collect();
+
+        for id in 0..self.weigths.len() {
+            let weigths: blaf::Matrix = blaf::Matrix::from_vec(
+                self.weigths[id].nb_rows(),
+                self.weigths[id].nb_columns(),
+                self.weigths[id].data().iter().map(round).collect(),
+            )?;
+            let bias: blaf::ColumnVector = self.bias[id].iter().map(round).collect();
+
+            let neuron_inputs: blaf::ColumnVector =
+                layer_weighted_inputs(&weigths, &output, &bias)?
+                    .iter()
+                    .map(round)
+                    .collect();
+
+            output = self.activation_functions[id]
+                .activate(&neuron_inputs)
+                .iter()
+                .map(round)
+                .collect();
+        }
+
+        return Ok(output);
+    }
+
+    /// Predict mean and variance of output when input features are randomly dropped
+    /// For each of the given number of samples, each input feature is zeroed when the number
+    /// drawn for it by generator is lower than drop probability. Generator is expected to
+    /// give numbers uniformly distributed in [0, 1)
+    pub fn predict_input_dropout<Generator>(
+        &self,
+        input: &blaf::ColumnVector,
+        drop_prob: f64,
+        samples: usize,
+        generator: &Generator,
+    ) -> Result<(blaf::ColumnVector, blaf::ColumnVector), String>
+    where
+        Generator: NumberGenerator,
+    {
+        if samples == 0 {
+            return Err(String::from("Number of samples must be positive"));
+        }
+
+        let mut outputs: Vec<blaf::ColumnVector> = Vec::with_capacity(samples);
+
+        for _ in 0..samples {
+            let dropped_input: blaf::ColumnVector = input
+                .iter()
+                .zip(generator.generate_vec(input.len()).iter())
+                .map(|(&value, &draw)| if draw < drop_prob { 0.0 } else { value })
+                .collect();
+
+            outputs.push(self.predict(&dropped_input)?);
+        }
+
+        return Ok(output_statistics(&outputs));
+    }
+
+    /// Predict output with weights randomly dropped (DropConnect) for a single forward pass
+    /// Each weight is zeroed when the number drawn for it by generator is lower than drop
+    /// probability, and kept weights are scaled by 1 / (1 - drop probability). Generator is
+    /// expected to give numbers uniformly distributed in [0, 1)
+    pub fn predict_dropconnect<Generator>(
+        &self,
+        input: &blaf::ColumnVector,
+        drop_prob: f64,
+        generator: &Generator,
+    ) -> Result<blaf::ColumnVector, String>
+    where
+        Generator: NumberGenerator,
+    {
+        if !(0.0..1.0).contains(&drop_prob) {
+            return Err(String::from("Drop probability must be in [0, 1)"));
+        }
+
+        self.check_input(input)?;
+
+        let scale: f64 = 1.0 / (1.0 - drop_prob);
+        let mut output: blaf::ColumnVector = input.clone();
+
+        for id in 0..self.weigths.len() {
+            let weigths: &blaf::Matrix = &self.weigths[id];
+
+            let data: Vec<f64> = weigths
+                .data()
+                .iter()
+                .zip(generator.generate_vec(weigths.data().len()).iter())
+                .map(|(&weigth, &draw)| {
+                    if draw < drop_prob {
+                        0.0
+                    } else {
+                        scale * weigth
+                    }
+                })
+                .collect();
+
+            let dropped: blaf::Matrix =
+                blaf::Matrix::from_vec(weigths.nb_rows(), weigths.nb_columns(), data)?;
+
+            let neuron_inputs: blaf::ColumnVector =
+                layer_weighted_inputs(&dropped, &output, &self.bias[id])?;
+            output = self.activation_functions[id].activate(&neuron_inputs);
+        }
+
+        return Ok(output);
+    }
+
+    /// Predict mean and variance of output with Monte Carlo dropout
+    /// For each of the given number of samples, a dropout mask is drawn for each layer, where
+    /// a neuron is dropped when the number drawn for it by generator is lower than drop rate
+    /// of its layer, then a masked forward pass is done. Generator is expected to give numbers
+    /// uniformly distributed in [0, 1)
+    pub fn mc_dropout_predict<Generator>(
+        &self,
+        input: &blaf::ColumnVector,
+        drop_rates: &[f64],
+        samples: usize,
+        generator: &Generator,
+    ) -> Result<(blaf::ColumnVector, blaf::ColumnVector), String>
+    where
+        Generator: NumberGenerator,
+    {
+        if samples == 0 {
+            return Err(String::from("Number of samples must be positive"));
+        }
+
+        if drop_rates.len() != self.weigths.len() {
+            return Err(String::from(
+                "Number of drop rates must be equal to number of layers of neural network",
+            ));
+        }
+
+        let mut outputs: Vec<blaf::ColumnVector> = Vec::with_capacity(samples);
+
+        for _ in 0..samples {
+            let masks: Vec<Vec<bool>> = self
+                .weigths
+                .iter()
+                .zip(drop_rates.iter())
+                .map(|(weigths, &drop_rate)| {
+                    return generator
+                        .generate_vec(weigths.nb_rows())
+                        .iter()
+                        .map(|&draw| draw >= drop_rate)
+                        .collect();
+                })
+                .collect();
+
+            let mut layer_outputs: Vec<blaf::ColumnVector> =
+                self.forward_with_mask(input, &masks)?;
+
+            outputs.push(layer_outputs.pop().unwrap());
+        }
+
+        return Ok(output_statistics(&outputs));
+    }
+
+    /// Compute fraction of saturated neurons of each layer over inputs given in argument
+    /// A neuron is saturated when absolute value of its output exceeds given threshold,
+    /// i.e. it is near saturation region of bounded activation functions
+    pub fn saturation_report(
+        &self,
+        inputs: &[blaf::ColumnVector],
+        threshold: f64,
+    ) -> Result<Vec<f64>, String> {
+        if inputs.is_empty() {
+            return Err(String::from(
+                "There is no input to compute saturation report",
+            ));
+        }
+
+        let mut nb_saturated: Vec<usize> = vec![0; self.weigths.len()];
+
+        for input in inputs.iter() {
+            let traces: Vec<LayerTrace> = self.forward_trace(input)?;
+
+            for (id, trace) in traces.iter().enumerate() {
+                nb_saturated[id] += trace
+                    .post_activation
+                    .iter()
+                    .filter(|value| value.abs() > threshold)
+                    .count();
+            }
+        }
+
+        return Ok(nb_saturated
+            .iter()
+            .zip(self.weigths.iter())
+            .map(|(&count, weigths)| count as f64 / (weigths.nb_rows() * inputs.len()) as f64)
+            .collect());
+    }
+
+    /// Export neural network as a C header, so it can be compiled into firmware
+    /// Header contains layer dimensions (including input layer) as constants, then weights
+    /// in row-major order and bias of each layer as arrays of doubles prefixed by given name.
+    /// Name must be a valid C identifier, and weights and bias must be finite
+    pub fn to_c_header(&self, name: &str) -> Result<String, String> {
+        let is_valid_name: bool = name
+            .chars()
+            .next()
+            .is_some_and(|first| first.is_ascii_alphabetic() || first == '_')
+            && name
+                .chars()
+                .all(|character| character.is_ascii_alphanumeric() || character == '_');
+
+        if !is_valid_name {
+            return Err(format!("{} is not a valid C identifier", name));
+        }
+
+        let is_finite: bool = self
+            .weigths
+            .iter()
+            .all(|weigths| weigths.data().iter().all(|value| value.is_finite()))
+            && self
+                .bias
+                .iter()
+                .all(|bias| bias.iter().all(|value| value.is_finite()));
+
+        if !is_finite {
+            return Err(String::from(
+                "Weights and bias must be finite to be written as C literals",
+            ));
+        }
+
+        let format_values = |values: &[f64]| -> String {
+            return values
+                .iter()
+                .map(|value| format!("{:?}", value))
+                .collect::<Vec<String>>()
+                .join(", ");
+        };
+
+        let nb_neurons: Vec<usize> = self.layer_dimensions();
+        let guard: String = format!("{}_H", name.to_uppercase());
+
+        let mut header: String = String::new();
+
+        header.push_str(&format!("#ifndef {}\n#define {}\n\n", guard, guard));
+        header.push_str(&format!(
+            "static const unsigned int {}_nb_layers = {};\n",
+            name,
+            self.weigths.len()
+        ));
+        header.push_str(&format!(
+            "static const unsigned int {}_layer_dimensions[{}] = {{{}}};\n",
+            name,
+            nb_neurons.len(),
+            nb_neurons
+                .iter()
+                .map(|nb_neuron| nb_neuron.to_string())
+                .collect::<Vec<String>>()
+                .join(", ")
+        ));
+
+        for (id, (weigths, bias)) in self.weigths.iter().zip(self.bias.iter()).enumerate() {
+            header.push_str(&format!(
+                "\nstatic const double {}_weights_{}[{}] = {{{}}};\n",
+                name,
+                id,
+                weigths.data().len(),
+                format_values(weigths.data())
+            ));
+            header.push_str(&format!(
+                "static const double {}_bias_{}[{}] = {{{}}};\n",
+                name,
+                id,
+                bias.len(),
+                format_values(bias)
+            ));
+        }
+
+        header.push_str(&format!("\n#endif // {}\n", guard));
+
+        return Ok(header);
+    }
+
+    /// Compute correlation matrix of outputs of neurons of a layer over inputs given in argument
+    /// Element (i, j) is Pearson correlation between outputs of neurons i and j. Highly
+    /// correlated neurons are redundant. A neuron with constant output has zero correlation
+    /// with other neurons
+    pub fn activation_correlation(
+        &self,
+        inputs: &[blaf::ColumnVector],
+        layer: usize,
+    ) -> Result<blaf::Matrix, String> {
+        if inputs.is_empty() {
+            return Err(String::from("There is no input to compute correlation"));
+        }
+
+        let activations: Vec<blaf::ColumnVector> = inputs
+            .iter()
+            .map(|input| self.embed(input, layer))
+            .collect::<Result<Vec<blaf::ColumnVector>, String>>()?;
+
+        let nb_neurons: usize = activations[0].len();
+        let nb_samples: f64 = activations.len() as f64;
+
+        let mut means: Vec<f64> = vec![0.0; nb_neurons];
+
+        for activation in activations.iter() {
+            means
+                .iter_mut()
+                .zip(activation.iter())
+                .for_each(|(mean, value)| *mean += value / nb_samples);
+        }
+
+        let mut covariances: Vec<f64> = vec![0.0; nb_neurons * nb_neurons];
+
+        for activation in activations.iter() {
+            for i in 0..nb_neurons {
+                for j in 0..nb_neurons {
+                    covariances[i * nb_neurons + j] +=
+                        (activation[i] - means[i]) * (activation[j] - means[j]);
+                }
+            }
+        }
+
+        let mut correlations: Vec<f64> = vec![0.0; nb_neurons * nb_neurons];
+
+        for i in 0..nb_neurons {
+            for j in 0..nb_neurons {
+                let norm: f64 =
+                    (covariances[i * nb_neurons + i] * covariances[j * nb_neurons + j]).sqrt();
+
+                correlations[i * nb_neurons + j] = if i == j {
+                    1.0
+                } else if norm > 0.0 {
+                    covariances[i * nb_neurons + j] / norm
+                } else {
+                    0.0
+                };
+            }
+        }
+
+        return blaf::Matrix::from_vec(nb_neurons, nb_neurons, correlations);
+    }
+
+    /// Fit bias of output neuron of a single-output neural network in closed form
+    /// Bias is set to value minimizing mean squared error on weighted input of output neuron
+    /// given fixed weights, i.e. bias is shifted by mean residual. It is exact when output
+    /// activation function is the identity, and provides a cheap warm start otherwise
+    pub fn fit_output_bias_closed_form(
+        &mut self,
+        inputs: &[blaf::ColumnVector],
+        targets: &[blaf::ColumnVector],
+    ) -> Result<(), String> {
+        let last: usize = self.weigths.len() - 1;
+
+        if self.weigths[last].nb_rows() != 1 {
+            return Err(String::from(
+                "Closed form bias fitting needs a neural network with one output",
+            ));
+        }
+
+        if inputs.is_empty() || inputs.len() != targets.len() {
+            return Err(String::from(
+                "Number of inputs and targets must be equal and non zero",
+            ));
+        }
+
+        let mut residual: f64 = 0.0;
+
+        for (input, target) in inputs.iter().zip(targets.iter()) {
+            if target.len() != 1 {
+                return Err(String::from(
+                    "Size of target is not consistent with topology of neural network",
+                ));
+            }
+
+            let traces: Vec<LayerTrace> = self.forward_trace(input)?;
+            residual += target[0] - traces[last].pre_activation[0];
+        }
+
+        self.bias[last][0] += residual / inputs.len() as f64;
+
+        return Ok(());
+    }
+
+    /// Compute an upper bound of Lipschitz constant of neural network
+    /// It is the product of spectral norms of weight matrices, assuming that
+    /// activation functions are 1-Lipschitz
+    pub fn lipschitz_bound(&self) -> f64 {
+        return self
+            .weigths
+            .iter()
+            .map(|weigths| weigths.spectral_norm())
+            .product::<f64>();
+    }
+
+    /// Count weights of neural network whose absolute value exceeds given threshold
+    /// Unlike the total number of weights, it gives the capacity of a sparse network
+    pub fn effective_parameter_count(&self, threshold: f64) -> usize {
+        return self
+            .weigths
+            .iter()
+            .map(|weigths| {
+                return (0..weigths.nb_rows())
+                    .map(|row| {
+                        return weigths
+                            .row(row)
+                            .iter()
+                            .filter(|weigth| weigth.abs() > threshold)
+                            .count();
+                    })
+                    .sum::<usize>();
+            })
+            .sum::<usize>();
+    }
+
+    /// Compute ratio of update magnitude to weight magnitude of each layer
+    /// Update of a layer is learning rate times its weight gradient given in argument,
+    /// and magnitudes are Frobenius norms. A healthy ratio is around 1e-3.
+    /// Ratio is undefined for a layer whose weights are all zero, so an error is returned
+    pub fn update_ratio(
+        &self,
+        weight_grads: &[blaf::Matrix],
+        learning_rate: f64,
+    ) -> Result<Vec<f64>, String> {
+        if weight_grads.len() != self.weigths.len() {
+            return Err(String::from(
+                "Number of weight gradients must be equal to number of layers of neural network",
+            ));
+        }
+
+        let frobenius_norm = |matrix: &blaf::Matrix| -> f64 {
+            return matrix.data().iter().map(|x| x * x).sum::<f64>().sqrt();
+        };
+
+        let mut ratios: Vec<f64> = Vec::with_capacity(self.weigths.len());
+
+        for (id, (weigths, grads)) in self.weigths.iter().zip(weight_grads.iter()).enumerate() {
+            if grads.nb_rows() != weigths.nb_rows() || grads.nb_columns() != weigths.nb_columns() {
+                return Err(format!(
+                    "Size of weight gradient of layer {} is not consistent with its weights",
+                    id
+                ));
+            }
+
+            let weigths_norm: f64 = frobenius_norm(weigths);
+
+            if weigths_norm == 0.0 {
+                return Err(format!(
+                    "Weights of layer {} are all zero, update ratio is undefined",
+                    id
+                ));
+            }
+
+            ratios.push(learning_rate * frobenius_norm(grads) / weigths_norm);
+        }
+
+        return Ok(ratios);
+    }
+}
+
+/// Compute cosine similarity between two gradient sets, each made of weight gradients and
+/// bias gradients of a neural network. A negative value indicates conflicting gradients
+pub fn gradient_cosine(
+    weight_grads_a: &[blaf::Matrix],
+    bias_grads_a: &[blaf::ColumnVector],
+    weight_grads_b: &[blaf::Matrix],
+    bias_grads_b: &[blaf::ColumnVector],
+) -> Result<f64, String> {
+    let flat_a: blaf::ColumnVector = blaf::flatten(weight_grads_a, bias_grads_a);
+    let flat_b: blaf::ColumnVector = blaf::flatten(weight_grads_b, bias_grads_b);
+
+    return blaf::cosine_similarity(&flat_a, &flat_b);
+}
+
+// Unit test
+#[cfg(test)]
+mod tests {
+    use super::super::activation_fn::softmax::Softmax;
+    use super::super::loss::LogCosh;
+    use super::super::num_gen::he::HeGenerator;
+    use super::super::num_gen::orthogonal::OrthogonalGenerator;
+    use super::super::num_gen::xavier::XavierGenerator;
+    use super::super::topology::TopologyBuilder;
+    use super::*;
+
+    // Number generator to fill matrix with zero
+    #[derive(Default)]
+    struct ZeroGenerator {}
+
+    impl NumberGenerator for ZeroGenerator {
+        fn generate_vec(&self, size: usize) -> Vec<f64> {
+            return vec![0.0; size];
+        }
+    }
+
+    // Activation function for test
+    #[derive(Default, Clone)]
+    struct TestActivationFn {}
+
+    impl ActivationFunction for TestActivationFn {
+        fn activate(&self, x: f64) -> f64 {
+            return x;
+        }
+    }
+
+    #[test]
+    fn test_neural_net_new() {
+        let nb_neurons: Vec<usize> = vec![2, 3, 1];
+
+        let topology: Topology = TopologyBuilder::new()
+            .nb_input(nb_neurons[0])
+            .add_layer(nb_neurons[1], Box::new(TestActivationFn::default()))
+            .add_layer(nb_neurons[2], Box::new(TestActivationFn::default()))
+            .build()
+            .unwrap();
+
+        let generator: ZeroGenerator = ZeroGenerator::default();
+
+        let neural_net: NeuralNet = NeuralNet::new(topology, &generator);
+
+        let size: usize = nb_neurons.len() - 1;
+
+        assert_eq!(neural_net.weigths.len(), size);
+        assert_eq!(neural_net.bias.len(), size);
+        assert_eq!(neural_net.activation_functions.len(), size);
+
+        for id in 0..size {
+            assert_eq!(neural_net.weigths[id].nb_rows(), nb_neurons[id + 1]);
+            assert_eq!(neural_net.weigths[id].nb_columns(), nb_neurons[id]);
+            assert_eq!(neural_net.bias[id].len(), nb_neurons[id + 1]);
+        }
+    }
+
+    // Number generator to fill matrix with one
+    #[derive(Default)]
+    struct OneGenerator {}
+
+    impl NumberGenerator for OneGenerator {
+        fn generate_vec(&self, size: usize) -> Vec<f64> {
+            return vec![1.0; size];
+        }
+    }
+
+    // Function to check if two numbers are approximatively equal
+    fn approx_equal(value: f64, reference: f64, precision: f64) -> bool {
+        let mut error: f64 = (value - reference).abs();
+
+        if reference != 0.0 {
+            error /= reference.abs();
+        }
+
+        return error < precision;
+    }
+
+    #[test]
+    fn test_neural_net_predict_with_perceptron() {
+        let topology: Topology = TopologyBuilder::new()
+            .nb_input(2)
+            .add_layer(1, Box::new(TestActivationFn::default()))
+            .build()
+            .unwrap();
+
+        let generator: OneGenerator = OneGenerator::default();
+
+        let neural_net: NeuralNet = NeuralNet::new(topology, &generator);
+        let input: Vec<f64> = vec![1.0, 2.0];
+
+        match neural_net.predict(&input) {
+            Ok(output) => {
+                assert_eq!(output.len(), 1);
+                assert!(approx_equal(
+                    output[0],
+                    input.iter().sum::<f64>() + 1.0,
+                    0.01
+                ))
+            }
+            Err(_) => assert!(false),
+        }
+    }
+
+    #[test]
+    fn test_neural_net_predict_with_simple_topology() {
+        let topology: Topology = TopologyBuilder::new()
+            .nb_input(2)
+            .add_layer(2, Box::new(TestActivationFn::default()))
+            .add_layer(1, Box::new(TestActivationFn::default()))
+            .build()
+            .unwrap();
+
+        let generator: OneGenerator = OneGenerator::default();
+
+        let neural_net: NeuralNet = NeuralNet::new(topology, &generator);
+        let input: Vec<f64> = vec![1.0, 2.0];
+
+        match neural_net.predict(&input) {
+            Ok(output) => {
+                assert_eq!(output.len(), 1);
+                assert!(approx_equal(
+                    output[0],
+                    (input.iter().sum::<f64>() + 1.0) * 2.0 + 1.0,
+                    0.01
+                ))
+            }
+            Err(_) => assert!(false),
+        }
+    }
+
+    #[test]
+    fn test_neural_net_predict_should_return_error() {
+        let topology: Topology = TopologyBuilder::new()
+            .nb_input(2)
+            .add_layer(2, Box::new(TestActivationFn::default()))
+            .add_layer(1, Box::new(TestActivationFn::default()))
+            .build()
+            .unwrap();
+
+        let generator: OneGenerator = OneGenerator::default();
+
+        let neural_net: NeuralNet = NeuralNet::new(topology, &generator);
+        let input: Vec<f64> = vec![1.0, 2.0, 3.0];
+
+        match neural_net.predict(&input) {
+            Ok(_) => assert!(false),
+            Err(error) => assert_eq!(
+                error,
+                NenufarError::DimensionMismatch {
+                    expected: 2,
+                    found: 3
+                }
+            ),
+        }
+    }
+
+    // Number generator to fill matrix with minus one
+    #[derive(Default)]
+    struct MinusOneGenerator {}
+
+    impl NumberGenerator for MinusOneGenerator {
+        fn generate_vec(&self, size: usize) -> Vec<f64> {
+            return vec![-1.0; size];
+        }
+    }
+
+    // Rectified linear unit activation function for test
+    #[derive(Default, Clone)]
+    struct TestReluFn {}
+
+    impl ActivationFunction for TestReluFn {
+        fn activate(&self, x: f64) -> f64 {
+            return x.max(0.0);
+        }
+    }
+
+    #[test]
+    fn test_neural_net_forward_trace_with_linear_network() {
+        let topology: Topology = TopologyBuilder::new()
+            .nb_input(2)
+            .add_layer(3, Box::new(TestActivationFn::default()))
+            .add_layer(1, Box::new(TestActivationFn::default()))
+            .build()
+            .unwrap();
+
+        let generator: MinusOneGenerator = MinusOneGenerator::default();
+
+        let neural_net: NeuralNet = NeuralNet::new(topology, &generator);
+        let input: Vec<f64> = vec![1.0, 2.0];
+
+        match neural_net.forward_trace(&input) {
+            Ok(traces) => {
+                assert_eq!(traces.len(), 2);
+                assert_eq!(traces[0].pre_activation.len(), 3);
+                assert_eq!(traces[1].pre_activation.len(), 1);
+
+                for trace in traces.iter() {
+                    assert_eq!(trace.pre_activation.len(), trace.post_activation.len());
+
+                    for id in 0..trace.pre_activation.len() {
+                        assert!(approx_equal(
+                            trace.post_activation[id],
+                            trace.pre_activation[id],
+                            0.01
+                        ));
+                    }
+                }
+
+                let output: blaf::ColumnVector = neural_net.predict(&input).unwrap();
+                assert!(approx_equal(traces[1].post_activation[0], output[0], 0.01));
+            }
+            Err(_) => assert!(false),
+        }
+    }
+
+    #[test]
+    fn test_neural_net_forward_trace_with_relu_network() {
+        let topology: Topology = TopologyBuilder::new()
+            .nb_input(2)
+            .add_layer(2, Box::new(TestReluFn::default()))
+            .build()
+            .unwrap();
+
+        let generator: MinusOneGenerator = MinusOneGenerator::default();
+
+        let neural_net: NeuralNet = NeuralNet::new(topology, &generator);
+        let input: Vec<f64> = vec![1.0, 2.0];
+
+        match neural_net.forward_trace(&input) {
+            Ok(traces) => {
+                assert_eq!(traces.len(), 1);
+
+                for id in 0..2 {
+                    assert!(approx_equal(traces[0].pre_activation[id], -4.0, 0.01));
+                    assert!(approx_equal(traces[0].post_activation[id], 0.0, 0.01));
+                }
+            }
+            Err(_) => assert!(false),
+        }
+    }
+
+    #[test]
+    fn test_neural_net_from_topology_with_output_layer() {
+        let topology: Topology = TopologyBuilder::new()
+            .nb_input(2)
+            .add_layer(3, Box::new(TestReluFn::default()))
+            .output_layer(2, Box::new(TestActivationFn::default()))
+            .build()
+            .unwrap();
+
+        let neural_net: NeuralNet = NeuralNet::new(topology, &OneGenerator::default());
+
+        // Hidden layer gives [4, 4, 4], then output layer gives [13, 13]
+        match neural_net.forward(&vec![1.0, 2.0]) {
+            Ok(pass) => {
+                assert_eq!(pass.activations.len(), 2);
+                assert_eq!(pass.activations[0].len(), 3);
+                assert_eq!(pass.activations[1].len(), 2);
+
+                for elem in pass.activations[1].iter() {
+                    assert!(approx_equal(*elem, 13.0, 0.01));
+                }
+            }
+            Err(_) => assert!(false),
+        }
+
+        match neural_net.predict(&vec![1.0, 2.0]) {
+            Ok(output) => assert_eq!(output.len(), 2),
+            Err(_) => assert!(false),
+        }
+    }
+
+    #[test]
+    fn test_neural_net_with_xavier_initialization() {
+        let topology: Topology = TopologyBuilder::new()
+            .nb_input(40)
+            .add_layer(20, Box::new(TestTanhFn::default()))
+            .add_layer(10, Box::new(TestTanhFn::default()))
+            .build()
+            .unwrap();
+
+        let neural_net: NeuralNet = NeuralNet::new(topology, &XavierGenerator::new(5));
+
+        for (id, (fan_in, fan_out)) in [(40, 20), (20, 10)].iter().enumerate() {
+            let limit: f64 = (6.0 / (fan_in + fan_out) as f64).sqrt();
+
+            assert_eq!(neural_net.weigths[id].nb_rows(), *fan_out);
+            assert_eq!(neural_net.weigths[id].nb_columns(), *fan_in);
+            assert!(neural_net.weigths[id]
+                .data()
+                .iter()
+                .all(|w| w.abs() <= limit));
+            assert!(neural_net.bias[id].iter().all(|&b| b == 0.0));
+        }
+    }
+
+    #[test]
+    fn test_neural_net_with_he_initialization() {
+        let topology: Topology = TopologyBuilder::new()
+            .nb_input(200)
+            .add_layer(100, Box::new(TestReluFn::default()))
+            .add_layer(2, Box::new(TestActivationFn::default()))
+            .build()
+            .unwrap();
+
+        let neural_net: NeuralNet = NeuralNet::new(topology, &HeGenerator::new(5));
+
+        let weigths: &[f64] = neural_net.weigths[0].data();
+        let variance: f64 = weigths.iter().map(|w| w * w).sum::<f64>() / weigths.len() as f64;
+
+        assert!(approx_equal(variance, 2.0 / 200.0, 0.05));
+        assert!(neural_net.bias.iter().flatten().all(|&b| b == 0.0));
+    }
+
+    #[test]
+    fn test_neural_net_with_orthogonal_initialization() {
+        let topology: Topology = TopologyBuilder::new()
+            .nb_input(8)
+            .add_layer(8, Box::new(TestTanhFn::default()))
+            .add_layer(2, Box::new(TestActivationFn::default()))
+            .build()
+            .unwrap();
+
+        let neural_net: NeuralNet = NeuralNet::new(topology, &OrthogonalGenerator::new(1.0, 5));
+
+        // Square layer times its transpose is identity
+        let weigths: &blaf::Matrix = &neural_net.weigths[0];
+        let product: blaf::Matrix = blaf::gemm(weigths, &weigths.transpose()).unwrap();
+
+        for row in 0..8 {
+            for col in 0..8 {
+                let reference: f64 = if row == col { 1.0 } else { 0.0 };
+                assert!((product[(row, col)] - reference).abs() < 1e-9);
+            }
+        }
+
+        assert!(neural_net.bias.iter().flatten().all(|&b| b == 0.0));
+    }
+
+    #[test]
+    fn test_neural_net_predict_batch() {
+        let topology: Topology = TopologyBuilder::new()
+            .nb_input(3)
+            .add_layer(4, Box::new(TestTanhFn::default()))
+            .add_vector_layer(2, Box::new(Softmax::default()))
+            .build()
+            .unwrap();
+
+        let neural_net: NeuralNet = NeuralNet::new(topology, &FractionGenerator::default());
+
+        let inputs: Vec<blaf::ColumnVector> = (0..5)
+            .map(|index| vec![index as f64 * 0.3, 1.0 - index as f64 * 0.2, -0.5])
+            .collect();
+
+        match neural_net.predict_batch(&inputs) {
+            Ok(outputs) => {
+                assert_eq!(outputs.len(), inputs.len());
+
+                for (input, output) in inputs.iter().zip(outputs.iter()) {
+                    let reference: blaf::ColumnVector = neural_net.predict(input).unwrap();
+
+                    assert_eq!(output.len(), reference.len());
+
+                    for id in 0..output.len() {
+                        assert!(approx_equal(output[id], reference[id], 1e-9));
+                    }
+                }
+            }
+            Err(_) => assert!(false),
+        }
+
+        match neural_net.predict_batch(&[]) {
+            Ok(outputs) => assert!(outputs.is_empty()),
+            Err(_) => assert!(false),
+        }
+
+        match neural_net.predict_batch(&[vec![1.0, 2.0, 3.0], vec![1.0, 2.0]]) {
+            Ok(_) => assert!(false),
+            Err(message) => assert_eq!(
+                message,
+                String::from(NenufarError::DimensionMismatch {
+                    expected: 3,
+                    found: 2
+                })
+            ),
+        }
+    }
+
+    #[test]
+    fn test_neural_net_boolean_networks() {
+        let inputs: Vec<blaf::ColumnVector> = vec![
+            vec![0.0, 0.0],
+            vec![0.0, 1.0],
+            vec![1.0, 0.0],
+            vec![1.0, 1.0],
+        ];
+
+        let cases: Vec<(NeuralNet, Vec<f64>)> = vec![
+            (NeuralNet::xor_network(), vec![0.0, 1.0, 1.0, 0.0]),
+            (NeuralNet::and_network(), vec![0.0, 0.0, 0.0, 1.0]),
+            (NeuralNet::or_network(), vec![0.0, 1.0, 1.0, 1.0]),
+        ];
+
+        for (neural_net, references) in cases.iter() {
+            for (input, reference) in inputs.iter().zip(references.iter()) {
+                match neural_net.predict(input) {
+                    Ok(output) => {
+                        assert_eq!(output.len(), 1);
+                        assert!((output[0] - reference).abs() < 1e-3);
+                    }
+                    Err(_) => assert!(false),
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_neural_net_clone() {
+        let topology: Topology = TopologyBuilder::new()
+            .nb_input(2)
+            .add_layer(3, Box::new(TestTanhFn::default()))
+            .add_vector_layer(2, Box::new(Softmax::default()))
+            .build()
+            .unwrap();
+
+        let neural_net: NeuralNet = NeuralNet::new(topology.clone(), &FractionGenerator::default());
+        let mut cloned: NeuralNet = neural_net.clone();
+        let input: blaf::ColumnVector = vec![0.4, -1.2];
+
+        assert_eq!(
+            cloned.predict(&input).unwrap(),
+            neural_net.predict(&input).unwrap()
+        );
+
+        // Training clone does not change original neural network
+        let reference: blaf::ColumnVector = neural_net.predict(&input).unwrap();
+        cloned
+            .train(&[(input.clone(), vec![1.0, 0.0])], 0.5, 5)
+            .unwrap();
+
+        assert_eq!(neural_net.predict(&input).unwrap(), reference);
+        assert_ne!(cloned.predict(&input).unwrap(), reference);
+
+        // Network built from cloned topology is identical
+        let other: NeuralNet = NeuralNet::new(topology, &FractionGenerator::default());
+        assert_eq!(other.predict(&input).unwrap(), reference);
+    }
+
+    #[test]
+    fn test_neural_net_forward() {
+        let topology: Topology = TopologyBuilder::new()
+            .nb_input(2)
+            .add_layer(2, Box::new(TestReluFn::default()))
+            .add_layer(1, Box::new(TestActivationFn::default()))
+            .build()
+            .unwrap();
+
+        let generator: MinusOneGenerator = MinusOneGenerator::default();
+
+        let neural_net: NeuralNet = NeuralNet::new(topology, &generator);
+        let input: Vec<f64> = vec![1.0, 2.0];
+
+        match neural_net.forward(&input) {
+            Ok(pass) => {
+                assert_eq!(pass.weighted_inputs.len(), 2);
+                assert_eq!(pass.activations.len(), 2);
+
+                for id in 0..2 {
+                    assert!(approx_equal(pass.weighted_inputs[0][id], -4.0, 0.01));
+                    assert!(approx_equal(pass.activations[0][id], 0.0, 0.01));
+                }
+
+                assert!(approx_equal(pass.weighted_inputs[1][0], -1.0, 0.01));
+                assert!(approx_equal(pass.activations[1][0], -1.0, 0.01));
+
+                let output: blaf::ColumnVector = neural_net.predict(&input).unwrap();
+                assert_eq!(pass.activations[1], output);
+            }
+            Err(_) => assert!(false),
+        }
+
+        assert!(neural_net.forward(&vec![1.0]).is_err());
+    }
+
+    #[test]
+    fn test_neural_net_forward_trace_should_return_error() {
+        let topology: Topology = TopologyBuilder::new()
+            .nb_input(2)
+            .add_layer(1, Box::new(TestActivationFn::default()))
+            .build()
+            .unwrap();
+
+        let generator: OneGenerator = OneGenerator::default();
+
+        let neural_net: NeuralNet = NeuralNet::new(topology, &generator);
+        let input: Vec<f64> = vec![1.0, 2.0, 3.0];
+
+        assert!(neural_net.forward_trace(&input).is_err());
+    }
+
+    // Number generator to fill matrix with large numbers
+    #[derive(Default)]
+    struct LargeGenerator {}
+
+    impl NumberGenerator for LargeGenerator {
+        fn generate_vec(&self, size: usize) -> Vec<f64> {
+            return vec![1000.0; size];
+        }
+    }
+
+    #[test]
+    fn test_neural_net_predict_with_activation_bounds() {
+        let topology: Topology = TopologyBuilder::new()
+            .nb_input(2)
+            .add_layer(2, Box::new(TestActivationFn::default()))
+            .add_layer(1, Box::new(TestActivationFn::default()))
+            .build()
+            .unwrap();
+
+        let generator: OneGenerator = OneGenerator::default();
+
+        let neural_net: NeuralNet = NeuralNet::new(topology, &generator);
+        let input: Vec<f64> = vec![1.0, 2.0];
+
+        match neural_net.predict_with_activation_bounds(&input, 100.0) {
+            Ok(output) => {
+                let reference: blaf::ColumnVector = neural_net.predict(&input).unwrap();
+
+                assert_eq!(output.len(), 1);
+                assert!(approx_equal(output[0], reference[0], 0.01));
+            }
+            Err(_) => assert!(false),
+        }
+    }
+
+    #[test]
+    fn test_neural_net_predict_with_activation_bounds_should_return_error() {
+        let topology: Topology = TopologyBuilder::new()
+            .nb_input(2)
+            .add_layer(2, Box::new(TestActivationFn::default()))
+            .add_layer(1, Box::new(TestActivationFn::default()))
+            .build()
+            .unwrap();
+
+        let generator: LargeGenerator = LargeGenerator::default();
+
+        let neural_net: NeuralNet = NeuralNet::new(topology, &generator);
+        let input: Vec<f64> = vec![1.0, 2.0];
+
+        match neural_net.predict_with_activation_bounds(&input, 100.0) {
+            Ok(_) => assert!(false),
+            Err(message) => assert!(message.contains("layer 0")),
+        }
+    }
+
+    #[test]
+    fn test_gradient_cosine() {
+        let one_generator: OneGenerator = OneGenerator::default();
+        let minus_one_generator: MinusOneGenerator = MinusOneGenerator::default();
+
+        let weight_grads: Vec<blaf::Matrix> = vec![blaf::Matrix::new(2, 3, &one_generator)];
+        let bias_grads: Vec<blaf::ColumnVector> = vec![vec![1.0, 1.0]];
+
+        let opposite_weight_grads: Vec<blaf::Matrix> =
+            vec![blaf::Matrix::new(2, 3, &minus_one_generator)];
+        let opposite_bias_grads: Vec<blaf::ColumnVector> = vec![vec![-1.0, -1.0]];
+
+        match gradient_cosine(&weight_grads, &bias_grads, &weight_grads, &bias_grads) {
+            Ok(cosine) => assert!(approx_equal(cosine, 1.0, 0.01)),
+            Err(_) => assert!(false),
+        }
+
+        match gradient_cosine(
+            &weight_grads,
+            &bias_grads,
+            &opposite_weight_grads,
+            &opposite_bias_grads,
+        ) {
+            Ok(cosine) => assert!(approx_equal(cosine, -1.0, 0.01)),
+            Err(_) => assert!(false),
+        }
+    }
+
+    #[test]
+    fn test_neural_net_fit_temperature() {
+        let topology: Topology = TopologyBuilder::new()
+            .nb_input(2)
+            .add_layer(2, Box::new(TestActivationFn::default()))
+            .build()
+            .unwrap();
+
+        let generator: ZeroGenerator = ZeroGenerator::default();
+
+        let mut neural_net: NeuralNet = NeuralNet::new(topology, &generator);
+
+        // Over-confident logits predicting first class while a quarter of targets are second class
+        let logits: Vec<blaf::ColumnVector> = vec![vec![5.0, 0.0]; 4];
+        let targets: Vec<blaf::ColumnVector> = vec![
+            vec![1.0, 0.0],
+            vec![1.0, 0.0],
+            vec![1.0, 0.0],
+            vec![0.0, 1.0],
+        ];
+
+        match neural_net.fit_temperature(&logits, &targets) {
+            Ok(temperature) => {
+                assert!(
+                    temperature_cross_entropy(&logits, &targets, temperature)
+                        < temperature_cross_entropy(&logits, &targets, 1.0)
+                );
+
+                // Optimal probability of first class is 0.75, so 5 / T = ln(3)
+                assert!(approx_equal(temperature, 5.0 / 3.0_f64.ln(), 0.01));
+            }
+            Err(_) => assert!(false),
+        }
+
+        match neural_net.predict_calibrated(&vec![1.0, 2.0]) {
+            Ok(output) => {
+                assert_eq!(output.len(), 2);
+                assert!(approx_equal(output[0], 0.5, 0.01));
+            }
+            Err(_) => assert!(false),
+        }
+    }
+
+    #[test]
+    fn test_neural_net_fit_temperature_should_return_error() {
+        let topology: Topology = TopologyBuilder::new()
+            .nb_input(2)
+            .add_layer(2, Box::new(TestActivationFn::default()))
+            .build()
+            .unwrap();
+
+        let generator: ZeroGenerator = ZeroGenerator::default();
+
+        let mut neural_net: NeuralNet = NeuralNet::new(topology, &generator);
+
+        assert!(neural_net.fit_temperature(&[], &[]).is_err());
+        assert!(neural_net
+            .fit_temperature(&[vec![1.0, 0.0]], &[vec![1.0, 0.0], vec![0.0, 1.0]])
+            .is_err());
+        assert!(neural_net
+            .fit_temperature(&[vec![1.0, 0.0]], &[vec![1.0, 0.0, 0.0]])
+            .is_err());
+    }
+
+    // Number generator to fill matrix with sequence 0, 1, 2, ...
+    #[derive(Default)]
+    struct SequenceGenerator {}
+
+    impl NumberGenerator for SequenceGenerator {
+        fn generate_vec(&self, size: usize) -> Vec<f64> {
+            return (0..size).map(|index| index as f64).collect();
+        }
+    }
+
+    #[test]
+    fn test_neural_net_predict_or_abstain() {
+        let topology: Topology = TopologyBuilder::new()
+            .nb_input(1)
+            .add_layer(2, Box::new(TestActivationFn::default()))
+            .build()
+            .unwrap();
+
+        let generator: SequenceGenerator = SequenceGenerator::default();
+
+        // Output of neural network is [0, x + 1]
+        let neural_net: NeuralNet = NeuralNet::new(topology, &generator);
+
+        match neural_net.predict_or_abstain(&vec![9.0], 0.9) {
+            Ok(prediction) => assert_eq!(prediction, Some(1)),
+            Err(_) => assert!(false),
+        }
+
+        // Uniform distribution
+        match neural_net.predict_or_abstain(&vec![-1.0], 0.6) {
+            Ok(prediction) => assert_eq!(prediction, None),
+            Err(_) => assert!(false),
+        }
+
+        assert!(neural_net.predict_or_abstain(&vec![1.0, 2.0], 0.5).is_err());
+    }
+
+    #[test]
+    fn test_neural_net_smoothed_predict() {
+        let topology: Topology = TopologyBuilder::new()
+            .nb_input(2)
+            .add_layer(3, Box::new(TestTanhFn::default()))
+            .add_layer(3, Box::new(TestActivationFn::default()))
+            .build()
+            .unwrap();
+
+        let neural_net: NeuralNet = NeuralNet::new(topology, &FractionGenerator::default());
+        let input: blaf::ColumnVector = vec![0.5, -0.2];
+        let generator: UniformSequenceGenerator = UniformSequenceGenerator::default();
+
+        match neural_net.smoothed_predict(&input, 0.0, 10, &generator) {
+            Ok(distribution) => {
+                let reference: blaf::ColumnVector =
+                    blaf::softmax(&neural_net.predict(&input).unwrap());
+
+                assert_eq!(distribution.len(), reference.len());
+
+                for id in 0..distribution.len() {
+                    assert!(approx_equal(distribution[id], reference[id], 1e-9));
+                }
+            }
+            Err(_) => assert!(false),
+        }
+
+        match neural_net.smoothed_predict(&input, 0.5, 50, &generator) {
+            Ok(distribution) => {
+                assert!(approx_equal(distribution.iter().sum::<f64>(), 1.0, 1e-9));
+            }
+            Err(_) => assert!(false),
+        }
+
+        assert!(neural_net
+            .smoothed_predict(&input, 0.5, 0, &generator)
+            .is_err());
+        assert!(neural_net
+            .smoothed_predict(&vec![1.0], 0.5, 10, &generator)
+            .is_err());
+    }
+
+    #[test]
+    fn test_neural_net_batch_entropy() {
+        let topology: Topology = TopologyBuilder::new()
+            .nb_input(1)
+            .add_layer(2, Box::new(TestActivationFn::default()))
+            .build()
+            .unwrap();
+
+        let generator: SequenceGenerator = SequenceGenerator::default();
+
+        // Output of neural network is [0, x + 1]
+        let neural_net: NeuralNet = NeuralNet::new(topology, &generator);
+        let inputs: Vec<blaf::ColumnVector> = vec![vec![-1.0], vec![0.0]];
+
+        match neural_net.batch_entropy(&inputs) {
+            Ok(entropies) => {
+                assert_eq!(entropies.len(), inputs.len());
+
+                // Uniform distribution
+                assert!(approx_equal(entropies[0], 2.0_f64.ln(), 0.01));
+
+                // Distribution [1 / (1 + e), e / (1 + e)]
+                let p: f64 = 1.0 / (1.0 + 1.0_f64.exp());
+                let reference: f64 = -p * p.ln() - (1.0 - p) * (1.0 - p).ln();
+                assert!(approx_equal(entropies[1], reference, 0.01));
+            }
+            Err(_) => assert!(false),
+        }
+
+        assert!(neural_net.batch_entropy(&[vec![1.0, 2.0]]).is_err());
+    }
+
+    #[test]
+    fn test_neural_net_prune_neurons() {
+        let topology: Topology = TopologyBuilder::new()
+            .nb_input(1)
+            .add_layer(4, Box::new(TestActivationFn::default()))
+            .add_layer(2, Box::new(TestActivationFn::default()))
+            .build()
+            .unwrap();
+
+        let generator: SequenceGenerator = SequenceGenerator::default();
+
+        // Incoming weights of hidden neurons are 0, 1, 2 and 3
+        let mut neural_net: NeuralNet = NeuralNet::new(topology, &generator);
+
+        match neural_net.prune_neurons(0, 0.5) {
+            Ok(nb_removed) => {
+                assert_eq!(nb_removed, 2);
+
+                assert_eq!(neural_net.weigths[0].nb_rows(), 2);
+                assert_eq!(neural_net.weigths[0].nb_columns(), 1);
+                assert_eq!(neural_net.bias[0].len(), 2);
+                assert_eq!(neural_net.weigths[1].nb_rows(), 2);
+                assert_eq!(neural_net.weigths[1].nb_columns(), 2);
+
+                assert_eq!(neural_net.weigths[0].row(0), &[2.0]);
+                assert_eq!(neural_net.weigths[0].row(1), &[3.0]);
+                assert_eq!(neural_net.bias[0], vec![2.0, 3.0]);
+                assert_eq!(neural_net.weigths[1].row(0), &[2.0, 3.0]);
+            }
+            Err(_) => assert!(false),
+        }
+
+        match neural_net.predict(&vec![1.0]) {
+            Ok(output) => assert_eq!(output.len(), 2),
+            Err(_) => assert!(false),
+        }
+    }
+
+    #[test]
+    fn test_neural_net_prune_neurons_should_return_error() {
+        let topology: Topology = TopologyBuilder::new()
+            .nb_input(1)
+            .add_layer(4, Box::new(TestActivationFn::default()))
+            .add_layer(2, Box::new(TestActivationFn::default()))
+            .build()
+            .unwrap();
+
+        let generator: SequenceGenerator = SequenceGenerator::default();
+
+        let mut neural_net: NeuralNet = NeuralNet::new(topology, &generator);
+
+        assert!(neural_net.prune_neurons(1, 0.5).is_err());
+        assert!(neural_net.prune_neurons(0, 0.0).is_err());
+        assert!(neural_net.prune_neurons(0, 1.5).is_err());
+    }
+
+    #[test]
+    fn test_neural_net_set_activation() {
+        let topology: Topology = TopologyBuilder::new()
+            .nb_input(2)
+            .add_layer(1, Box::new(TestActivationFn::default()))
+            .build()
+            .unwrap();
+
+        let generator: MinusOneGenerator = MinusOneGenerator::default();
+
+        let mut neural_net: NeuralNet = NeuralNet::new(topology, &generator);
+        let input: Vec<f64> = vec![1.0, 2.0];
+
+        // Pre-activation is negative
+        assert!(approx_equal(
+            neural_net.predict(&input).unwrap()[0],
+            -4.0,
+            0.01
+        ));
+
+        match neural_net.set_activation(0, Box::new(TestReluFn::default())) {
+            Ok(_) => assert!(approx_equal(
+                neural_net.predict(&input).unwrap()[0],
+                0.0,
+                0.01
+            )),
+            Err(_) => assert!(false),
+        }
+
+        assert!(neural_net
+            .set_activation(1, Box::new(TestReluFn::default()))
+            .is_err());
+    }
+
+    #[test]
+    fn test_neural_net_embed() {
+        let topology: Topology = TopologyBuilder::new()
+            .nb_input(2)
+            .add_layer(3, Box::new(TestActivationFn::default()))
+            .add_layer(1, Box::new(TestActivationFn::default()))
+            .build()
+            .unwrap();
+
+        let generator: OneGenerator = OneGenerator::default();
+
+        let neural_net: NeuralNet = NeuralNet::new(topology, &generator);
+        let input: Vec<f64> = vec![1.0, 2.0];
+
+        match neural_net.embed(&input, 1) {
+            Ok(embedding) => {
+                let output: blaf::ColumnVector = neural_net.predict(&input).unwrap();
+
+                assert_eq!(embedding.len(), output.len());
+                assert!(approx_equal(embedding[0], output[0], 0.01));
+            }
+            Err(_) => assert!(false),
+        }
+
+        match neural_net.embed(&input, 0) {
+            Ok(embedding) => {
+                assert_eq!(embedding.len(), 3);
+
+                for value in embedding.iter() {
+                    assert!(approx_equal(*value, 4.0, 0.01));
+                }
+            }
+            Err(_) => assert!(false),
+        }
+
+        assert!(neural_net.embed(&input, 2).is_err());
+        assert!(neural_net.embed(&vec![1.0], 0).is_err());
+    }
+
+    #[test]
+    fn test_neural_net_invert_embedding() {
+        let topology: Topology = TopologyBuilder::new()
+            .nb_input(2)
+            .add_layer(2, Box::new(TestActivationFn::default()))
+            .add_layer(2, Box::new(TestActivationFn::default()))
+            .build()
+            .unwrap();
+
+        let mut neural_net: NeuralNet = NeuralNet::new(topology, &ZeroGenerator::default());
+
+        // Both layers are invertible linear maps
+        let mut state: NetworkState = neural_net.state();
+        state.weigths[0] = vec![2.0, 1.0, 1.0, 3.0];
+        state.weigths[1] = vec![1.0, 0.5, 0.0, 1.0];
+        state.bias[0] = vec![0.5, -0.5];
+        neural_net.load_state(state).unwrap();
+
+        let original: blaf::ColumnVector = vec![0.5, -1.0];
+        let generator: UniformSequenceGenerator = UniformSequenceGenerator::default();
+
+        for layer in 0..2 {
+            let target: blaf::ColumnVector = neural_net.embed(&original, layer).unwrap();
+
+            match neural_net.invert_embedding(&target, layer, 1000, 0.05, &generator) {
+                Ok(input) => {
+                    assert_eq!(input.len(), original.len());
+
+                    for id in 0..input.len() {
+                        assert!((input[id] - original[id]).abs() < 1e-3);
+                    }
+                }
+                Err(_) => assert!(false),
+            }
+        }
+
+        assert!(neural_net
+            .invert_embedding(&vec![1.0, 2.0], 2, 10, 0.05, &generator)
+            .is_err());
+        assert!(neural_net
+            .invert_embedding(&vec![1.0], 0, 10, 0.05, &generator)
+            .is_err());
+    }
+
+    #[test]
+    fn test_neural_net_update_ratio() {
+        let topology: Topology = TopologyBuilder::new()
+            .nb_input(2)
+            .add_layer(2, Box::new(TestActivationFn::default()))
+            .add_layer(1, Box::new(TestActivationFn::default()))
+            .build()
+            .unwrap();
+
+        let mut neural_net: NeuralNet = NeuralNet::new(topology, &ZeroGenerator::default());
+
+        // Weight norms are 5 and 2
+        let mut state: NetworkState = neural_net.state();
+        state.weigths[0] = vec![3.0, 0.0, 0.0, 4.0];
+        state.weigths[1] = vec![0.0, -2.0];
+        neural_net.load_state(state).unwrap();
+
+        // Gradient norms are 1 and 10
+        let weight_grads: Vec<blaf::Matrix> = vec![
+            blaf::Matrix::from_vec(2, 2, vec![0.6, 0.0, -0.8, 0.0]).unwrap(),
+            blaf::Matrix::from_vec(1, 2, vec![6.0, 8.0]).unwrap(),
+        ];
+
+        match neural_net.update_ratio(&weight_grads, 0.01) {
+            Ok(ratios) => {
+                assert_eq!(ratios.len(), 2);
+                assert!(approx_equal(ratios[0], 0.01 * 1.0 / 5.0, 1e-6));
+                assert!(approx_equal(ratios[1], 0.01 * 10.0 / 2.0, 1e-6));
+            }
+            Err(_) => assert!(false),
+        }
+
+        // Missing layer and inconsistent gradient size
+        assert!(neural_net.update_ratio(&weight_grads[..1], 0.01).is_err());
+        assert!(neural_net
+            .update_ratio(
+                &[
+                    weight_grads[0].clone(),
+                    blaf::Matrix::from_vec(2, 1, vec![6.0, 8.0]).unwrap()
+                ],
+                0.01
+            )
+            .is_err());
+    }
+
+    #[test]
+    fn test_neural_net_update_ratio_of_zero_weights() {
+        let topology: Topology = TopologyBuilder::new()
+            .nb_input(2)
+            .add_layer(1, Box::new(TestActivationFn::default()))
+            .build()
+            .unwrap();
+
+        let neural_net: NeuralNet = NeuralNet::new(topology, &ZeroGenerator::default());
+        let weight_grads: Vec<blaf::Matrix> =
+            vec![blaf::Matrix::from_vec(1, 2, vec![1.0, 1.0]).unwrap()];
+
+        assert!(neural_net.update_ratio(&weight_grads, 0.01).is_err());
+    }
+
+    #[test]
+    fn test_neural_net_effective_parameter_count() {
+        let topology: Topology = TopologyBuilder::new()
+            .nb_input(2)
+            .add_layer(3, Box::new(TestActivationFn::default()))
+            .add_layer(2, Box::new(TestActivationFn::default()))
+            .build()
+            .unwrap();
+
+        let generator: SequenceGenerator = SequenceGenerator::default();
+
+        // Weights are [0, ..., 5] in first layer and [0, ..., 5] in second layer
+        let neural_net: NeuralNet = NeuralNet::new(topology, &generator);
+
+        assert_eq!(neural_net.effective_parameter_count(0.5), 10);
+        assert_eq!(neural_net.effective_parameter_count(2.5), 6);
+        assert_eq!(neural_net.effective_parameter_count(10.0), 0);
+    }
+
+    // Number generator to fill matrix with one in first element and zero elsewhere
+    #[derive(Default)]
+    struct FirstOneGenerator {}
+
+    impl NumberGenerator for FirstOneGenerator {
+        fn generate_vec(&self, size: usize) -> Vec<f64> {
+            return (0..size)
+                .map(|index| if index == 0 { 1.0 } else { 0.0 })
+                .collect();
+        }
+    }
+
+    // Number generator to fill vector with decreasing sequence, used to reverse samples
+    #[derive(Default)]
+    struct ReverseGenerator {}
+
+    impl NumberGenerator for ReverseGenerator {
+        fn generate_vec(&self, size: usize) -> Vec<f64> {
+            return (0..size).map(|index| -(index as f64)).collect();
+        }
+    }
+
+    #[test]
+    fn test_neural_net_partial_dependence() {
+        let topology: Topology = TopologyBuilder::new()
+            .nb_input(2)
+            .add_layer(1, Box::new(TestActivationFn::default()))
+            .build()
+            .unwrap();
+
+        // Output of neural network is 0.3 * x1 + 0.6 * x2 + 0.3
+        let neural_net: NeuralNet = NeuralNet::new(topology, &FractionGenerator::default());
+        let inputs: Vec<blaf::ColumnVector> = vec![vec![1.0, 0.5], vec![-2.0, 1.5], vec![4.0, 0.0]];
+        let grid: Vec<f64> = vec![-1.0, 0.0, 0.5, 2.0];
+
+        match neural_net.partial_dependence(&inputs, 1, &grid) {
+            Ok(curve) => {
+                assert_eq!(curve.len(), grid.len());
+
+                // Curve is 0.3 * mean(x1) + 0.6 * value + 0.3, with mean(x1) = 1
+                for (value, dependence) in grid.iter().zip(curve.iter()) {
+                    assert!(approx_equal(*dependence, 0.6 + 0.6 * value, 1e-9));
+                }
+            }
+            Err(_) => assert!(false),
+        }
+
+        assert!(neural_net.partial_dependence(&inputs, 2, &grid).is_err());
+        assert!(neural_net.partial_dependence(&[], 0, &grid).is_err());
+    }
+
+    #[test]
+    fn test_neural_net_permutation_importance() {
+        let topology: Topology = TopologyBuilder::new()
+            .nb_input(2)
+            .add_layer(1, Box::new(TestActivationFn::default()))
+            .build()
+            .unwrap();
+
+        let generator: FirstOneGenerator = FirstOneGenerator::default();
+
+        // Output of neural network is x0 + 1, so it depends only on first feature
+        let neural_net: NeuralNet = NeuralNet::new(topology, &generator);
+
+        let inputs: Vec<blaf::ColumnVector> = (0..4)
+            .map(|index| vec![index as f64, (3 - index) as f64])
+            .collect();
+        let targets: Vec<blaf::ColumnVector> = inputs
+            .iter()
+            .map(|input| neural_net.predict(input).unwrap())
+            .collect();
+
+        let shuffle_generator: ReverseGenerator = ReverseGenerator::default();
+
+        match neural_net.permutation_importance(
+            &inputs,
+            &targets,
+            &LogCosh::default(),
+            &shuffle_generator,
+        ) {
+            Ok(importances) => {
+                assert_eq!(importances.len(), 2);
+                assert!(importances[0] > 0.1);
+                assert!(importances[1].abs() < 1e-12);
+            }
+            Err(_) => assert!(false),
+        }
+
+        assert!(neural_net
+            .permutation_importance(&inputs, &[], &LogCosh::default(), &shuffle_generator)
+            .is_err());
+    }
+
+    #[test]
+    fn test_neural_net_lipschitz_bound() {
+        let topology: Topology = TopologyBuilder::new()
+            .nb_input(2)
+            .add_layer(3, Box::new(TestActivationFn::default()))
+            .add_layer(2, Box::new(TestActivationFn::default()))
+            .build()
+            .unwrap();
+
+        let generator: SequenceGenerator = SequenceGenerator::default();
+
+        let neural_net: NeuralNet = NeuralNet::new(topology, &generator);
+
+        let reference: f64 =
+            neural_net.weigths[0].spectral_norm() * neural_net.weigths[1].spectral_norm();
+
+        assert!(approx_equal(neural_net.lipschitz_bound(), reference, 1e-9));
+
+        // Matrix of ones 3x2 has spectral norm sqrt(6), matrix of ones 2x3 too
+        let one_neural_net: NeuralNet = NeuralNet::new(
+            TopologyBuilder::new()
+                .nb_input(2)
+                .add_layer(3, Box::new(TestActivationFn::default()))
+                .add_layer(2, Box::new(TestActivationFn::default()))
+                .build()
+                .unwrap(),
+            &OneGenerator::default(),
+        );
+
+        assert!(approx_equal(one_neural_net.lipschitz_bound(), 6.0, 1e-6));
+    }
+
+    #[test]
+    fn test_neural_net_forward_with_mask() {
+        let topology: Topology = TopologyBuilder::new()
+            .nb_input(2)
+            .add_layer(4, Box::new(TestActivationFn::default()))
+            .add_layer(1, Box::new(TestActivationFn::default()))
+            .build()
+            .unwrap();
+
+        let generator: OneGenerator = OneGenerator::default();
+
+        let neural_net: NeuralNet = NeuralNet::new(topology, &generator);
+        let input: Vec<f64> = vec![1.0, 2.0];
+
+        // All true mask gives normal forward pass
+        let all_true: Vec<Vec<bool>> = vec![vec![true; 4], vec![true]];
+
+        match neural_net.forward_with_mask(&input, &all_true) {
+            Ok(outputs) => {
+                let traces: Vec<LayerTrace> = neural_net.forward_trace(&input).unwrap();
+
+                assert_eq!(outputs.len(), traces.len());
+
+                for (output, trace) in outputs.iter().zip(traces.iter()) {
+                    assert_eq!(output, &trace.post_activation);
+                }
+            }
+            Err(_) => assert!(false),
+        }
+
+        // Mask half of neurons of hidden layer
+        let masks: Vec<Vec<bool>> = vec![vec![true, false, true, false], vec![true]];
+
+        match neural_net.forward_with_mask(&input, &masks) {
+            Ok(outputs) => {
+                assert_eq!(outputs[0], vec![8.0, 0.0, 8.0, 0.0]);
+                assert!(approx_equal(outputs[1][0], 17.0, 0.01));
+            }
+            Err(_) => assert!(false),
+        }
+
+        assert!(neural_net
+            .forward_with_mask(&input, &[vec![true; 4]])
+            .is_err());
+        assert!(neural_net
+            .forward_with_mask(&input, &[vec![true; 3], vec![true]])
+            .is_err());
+    }
+
+    #[test]
+    fn test_neural_net_probe_input() {
+        let topology: Topology = TopologyBuilder::new()
+            .nb_input(3)
+            .add_layer(2, Box::new(TestActivationFn::default()))
+            .add_layer(1, Box::new(TestActivationFn::default()))
+            .build()
+            .unwrap();
+
+        let generator: SequenceGenerator = SequenceGenerator::default();
+
+        // First layer has weights [[0, 1, 2], [3, 4, 5]] and bias [0, 1],
+        // second layer has weights [[0, 1]] and bias [0]
+        let neural_net: NeuralNet = NeuralNet::new(topology, &generator);
+
+        for index in 0..3 {
+            match neural_net.probe_input(index, 1.0) {
+                Ok(output) => {
+                    let hidden: Vec<f64> = vec![index as f64, 3.0 + index as f64 + 1.0];
+                    let reference: f64 = 0.0 * hidden[0] + 1.0 * hidden[1] + 0.0;
+
+                    assert_eq!(output.len(), 1);
+                    assert!(approx_equal(output[0], reference, 0.01));
+                }
+                Err(_) => assert!(false),
+            }
+        }
+
+        assert!(neural_net.probe_input(3, 1.0).is_err());
+    }
+
+    #[test]
+    fn test_neural_net_state_round_trip() {
+        let build_topology = || -> Topology {
+            return TopologyBuilder::new()
+                .nb_input(2)
+                .add_layer(3, Box::new(TestActivationFn::default()))
+                .add_layer(1, Box::new(TestActivationFn::default()))
+                .build()
+                .unwrap();
+        };
+
+        let source: NeuralNet = NeuralNet::new(build_topology(), &SequenceGenerator::default());
+        let mut destination: NeuralNet =
+            NeuralNet::new(build_topology(), &ZeroGenerator::default());
+
+        let state: NetworkState = source.state();
+
+        assert_eq!(state.nb_neurons, vec![2, 3, 1]);
+        assert_eq!(state.weigths.len(), 2);
+        assert_eq!(state.weigths[0].len(), 6);
+        assert_eq!(state.bias[1].len(), 1);
+
+        match destination.load_state(state.clone()) {
+            Ok(_) => {
+                let input: Vec<f64> = vec![1.0, 2.0];
+
+                assert_eq!(
+                    destination.predict(&input).unwrap(),
+                    source.predict(&input).unwrap()
+                );
+                assert_eq!(destination.state(), state);
+            }
+            Err(_) => assert!(false),
+        }
+    }
+
+    #[test]
+    fn test_neural_net_set_parameters() {
+        let topology: Topology = TopologyBuilder::new()
+            .nb_input(2)
+            .add_layer(3, Box::new(TestActivationFn::default()))
+            .add_layer(1, Box::new(TestActivationFn::default()))
+            .build()
+            .unwrap();
+
+        let source: NeuralNet = NeuralNet::new(topology.clone(), &SequenceGenerator::default());
+        let mut destination: NeuralNet = NeuralNet::new(topology, &ZeroGenerator::default());
+
+        let parameters: blaf::ColumnVector = source.parameters();
+        assert_eq!(parameters.len(), 6 + 3 + 3 + 1);
+
+        match destination.set_parameters(&parameters) {
+            Ok(_) => assert_eq!(destination.state(), source.state()),
+            Err(_) => assert!(false),
+        }
+
+        assert!(destination.set_parameters(&parameters[1..]).is_err());
+    }
+
+    #[test]
+    fn test_neural_net_apply_stale_gradients() {
+        let topology: Topology = TopologyBuilder::new()
+            .nb_input(2)
+            .add_layer(3, Box::new(TestTanhFn::default()))
+            .add_layer(1, Box::new(TestActivationFn::default()))
+            .build()
+            .unwrap();
+
+        let mut neural_net: NeuralNet = NeuralNet::new(topology, &FractionGenerator::default());
+        let mut applier: StaleGradientApplier = StaleGradientApplier::new(0.1);
+
+        let input: blaf::ColumnVector = vec![0.5, -1.0];
+        let target: blaf::ColumnVector = vec![1.0];
+        let mse: MeanSquaredError = MeanSquaredError::default();
+
+        // Gradients are computed against version 0 of parameters
+        let (weight_grads, bias_grads) = neural_net
+            .parameter_gradients(&input, &target, &mse)
+            .unwrap();
+        let gradients: blaf::ColumnVector = blaf::flatten(&weight_grads, &bias_grads);
+        let initial: blaf::ColumnVector = neural_net.parameters();
+
+        // Gradients computed against current version are applied at full strength
+        match neural_net.apply_stale_gradients(&mut applier, &gradients, 0) {
+            Ok(staleness) => assert_eq!(staleness, 0),
+            Err(_) => assert!(false),
+        }
+
+        let updated: blaf::ColumnVector = neural_net.parameters();
+
+        for id in 0..gradients.len() {
+            assert!((updated[id] - (initial[id] - 0.1 * gradients[id])).abs() < 1e-12);
+        }
+
+        // Same gradients are now one version old, so they are down-weighted by 1 / 2
+        match neural_net.apply_stale_gradients(&mut applier, &gradients, 0) {
+            Ok(staleness) => assert_eq!(staleness, 1),
+            Err(_) => assert!(false),
+        }
+
+        let stale_updated: blaf::ColumnVector = neural_net.parameters();
+
+        for id in 0..gradients.len() {
+            assert!((stale_updated[id] - (updated[id] - 0.05 * gradients[id])).abs() < 1e-12);
+        }
+
+        assert_eq!(applier.version, 2);
+        assert!(neural_net
+            .apply_stale_gradients(&mut applier, &gradients[1..], 2)
+            .is_err());
+    }
+
+    #[test]
+    fn test_neural_net_load_state_should_return_error() {
+        let topology: Topology = TopologyBuilder::new()
+            .nb_input(2)
+            .add_layer(3, Box::new(TestActivationFn::default()))
+            .build()
+            .unwrap();
+
+        let mut neural_net: NeuralNet = NeuralNet::new(topology, &ZeroGenerator::default());
+
+        let mut state: NetworkState = neural_net.state();
+        state.nb_neurons = vec![2, 4];
+        assert!(neural_net.load_state(state).is_err());
+
+        let mut state: NetworkState = neural_net.state();
+        state.weigths[0].push(1.0);
+        assert!(neural_net.load_state(state).is_err());
+
+        let mut state: NetworkState = neural_net.state();
+        state.bias[0].push(1.0);
+        assert!(neural_net.load_state(state).is_err());
+    }
+
+    // Number generator to fill weights with one and bias with minus one
+    #[derive(Default)]
+    struct LineGenerator {}
+
+    impl NumberGenerator for LineGenerator {
+        fn generate_vec(&self, size: usize) -> Vec<f64> {
+            if size == 1 {
+                return vec![-1.0];
+            }
+
+            return vec![1.0; size];
+        }
+    }
+
+    #[test]
+    fn test_neural_net_decision_boundary_2d() {
+        let topology: Topology = TopologyBuilder::new()
+            .nb_input(2)
+            .add_layer(1, Box::new(TestActivationFn::default()))
+            .build()
+            .unwrap();
+
+        let generator: LineGenerator = LineGenerator::default();
+
+        // Output of neural network is x + y - 1
+        let neural_net: NeuralNet = NeuralNet::new(topology, &generator);
+
+        match neural_net.decision_boundary_2d((-1.0, 2.0), (-1.0, 2.0), 31, 0.5) {
+            Ok(points) => {
+                assert!(points.len() > 10);
+
+                for (x, y) in points.iter() {
+                    assert!((x + y - 1.5).abs() < 1e-9);
+                }
+            }
+            Err(_) => assert!(false),
+        }
+
+        assert!(neural_net
+            .decision_boundary_2d((-1.0, 2.0), (-1.0, 2.0), 1, 0.5)
+            .is_err());
+    }
+
+    #[test]
+    fn test_neural_net_decision_boundary_2d_should_return_error() {
+        let topology: Topology = TopologyBuilder::new()
+            .nb_input(3)
+            .add_layer(1, Box::new(TestActivationFn::default()))
+            .build()
+            .unwrap();
+
+        let neural_net: NeuralNet = NeuralNet::new(topology, &OneGenerator::default());
+
+        assert!(neural_net
+            .decision_boundary_2d((-1.0, 1.0), (-1.0, 1.0), 10, 0.0)
+            .is_err());
+    }
+
+    #[test]
+    fn test_neural_net_activations_to_csv() {
+        let topology: Topology = TopologyBuilder::new()
+            .nb_input(2)
+            .add_layer(3, Box::new(TestActivationFn::default()))
+            .add_layer(1, Box::new(TestActivationFn::default()))
+            .build()
+            .unwrap();
+
+        let generator: OneGenerator = OneGenerator::default();
+
+        let neural_net: NeuralNet = NeuralNet::new(topology, &generator);
+        let input: Vec<f64> = vec![1.0, 2.5];
+
+        let mut buffer: Vec<u8> = Vec::new();
+
+        match neural_net.activations_to_csv(&input, &mut buffer) {
+            Ok(_) => {
+                let csv: String = String::from_utf8(buffer).unwrap();
+                let rows: Vec<&str> = csv.lines().collect();
+
+                assert_eq!(rows.len(), 2);
+
+                let first: Vec<f64> = rows[0]
+                    .split(',')
+                    .map(|value| value.parse::<f64>().unwrap())
+                    .collect();
+                assert_eq!(first, vec![4.5; 3]);
+
+                let last: Vec<f64> = rows[1]
+                    .split(',')
+                    .map(|value| value.parse::<f64>().unwrap())
+                    .collect();
+                assert_eq!(last, neural_net.predict(&input).unwrap());
+            }
+            Err(_) => assert!(false),
+        }
+
+        assert!(neural_net
+            .activations_to_csv(&vec![1.0], &mut Vec::new())
+            .is_err());
+    }
+
+    // Number generator to fill matrix with multiples of 0.3
+    #[derive(Default)]
+    struct FractionGenerator {}
+
+    impl NumberGenerator for FractionGenerator {
+        fn generate_vec(&self, size: usize) -> Vec<f64> {
+            return (0..size).map(|index| (index as f64 + 1.0) * 0.3).collect();
+        }
+    }
+
+    #[test]
+    fn test_neural_net_predict_fixed_point() {
+        let topology: Topology = TopologyBuilder::new()
+            .nb_input(2)
+            .add_layer(3, Box::new(TestActivationFn::default()))
+            .add_layer(1, Box::new(TestActivationFn::default()))
+            .build()
+            .unwrap();
+
+        let generator: FractionGenerator = FractionGenerator::default();
+
+        let neural_net: NeuralNet = NeuralNet::new(topology, &generator);
+        let input: Vec<f64> = vec![0.7, -1.3];
+
+        let reference: f64 = neural_net.predict(&input).unwrap()[0];
+
+        match neural_net.predict_fixed_point(&input, 40) {
+            Ok(output) => assert!(approx_equal(output[0], reference, 1e-9)),
+            Err(_) => assert!(false),
+        }
+
+        match neural_net.predict_fixed_point(&input, 1) {
+            Ok(output) => assert!((output[0] - reference).abs() > 0.1),
+            Err(_) => assert!(false),
+        }
+
+        assert!(neural_net.predict_fixed_point(&vec![1.0], 8).is_err());
+    }
+
+    // Number generator giving numbers uniformly spread in [0, 1), changing at each call
+    #[derive(Default)]
+    struct UniformSequenceGenerator {
+        state: std::cell::Cell<u64>,
+    }
+
+    impl NumberGenerator for UniformSequenceGenerator {
+        fn generate_vec(&self, size: usize) -> Vec<f64> {
+            return (0..size)
+                .map(|_| {
+                    let state: u64 = self.state.get().wrapping_add(1);
+                    self.state.set(state);
+                    return ((state as f64) * 0.618033988749895).fract();
+                })
+                .collect();
+        }
+    }
+
+    #[test]
+    fn test_neural_net_predict_input_dropout() {
+        let topology: Topology = TopologyBuilder::new()
+            .nb_input(3)
+            .add_layer(2, Box::new(TestActivationFn::default()))
+            .build()
+            .unwrap();
+
+        let neural_net: NeuralNet = NeuralNet::new(topology, &OneGenerator::default());
+        let input: Vec<f64> = vec![1.0, 2.0, 3.0];
+
+        let generator: UniformSequenceGenerator = UniformSequenceGenerator::default();
+
+        match neural_net.predict_input_dropout(&input, 0.0, 10, &generator) {
+            Ok((mean, variance)) => {
+                let output: blaf::ColumnVector = neural_net.predict(&input).unwrap();
+
+                for id in 0..output.len() {
+                    assert!(approx_equal(mean[id], output[id], 1e-9));
+                }
+
+                assert!(variance.iter().all(|&elem| elem.abs() < 1e-12));
+            }
+            Err(_) => assert!(false),
+        }
+
+        match neural_net.predict_input_dropout(&input, 0.5, 100, &generator) {
+            Ok((mean, variance)) => {
+                assert!(mean[0] < 7.0);
+                assert!(variance.iter().all(|&elem| elem > 0.0));
+            }
+            Err(_) => assert!(false),
+        }
+
+        assert!(neural_net
+            .predict_input_dropout(&input, 0.5, 0, &generator)
+            .is_err());
+    }
+
+    #[test]
+    fn test_neural_net_predict_dropconnect() {
+        let topology: Topology = TopologyBuilder::new()
+            .nb_input(2)
+            .add_layer(4, Box::new(TestTanhFn::default()))
+            .add_layer(1, Box::new(TestActivationFn::default()))
+            .build()
+            .unwrap();
+
+        let neural_net: NeuralNet = NeuralNet::new(topology, &FractionGenerator::default());
+        let input: Vec<f64> = vec![1.0, -2.0];
+        let generator: UniformSequenceGenerator = UniformSequenceGenerator::default();
+        let reference: blaf::ColumnVector = neural_net.predict(&input).unwrap();
+
+        match neural_net.predict_dropconnect(&input, 0.0, &generator) {
+            Ok(output) => assert!(approx_equal(output[0], reference[0], 1e-12)),
+            Err(_) => assert!(false),
+        }
+
+        match neural_net.predict_dropconnect(&input, 0.5, &generator) {
+            Ok(output) => {
+                assert_eq!(output.len(), 1);
+                assert!(output[0].is_finite());
+                assert!((output[0] - reference[0]).abs() > 1e-6);
+            }
+            Err(_) => assert!(false),
+        }
+
+        assert!(neural_net
+            .predict_dropconnect(&input, 1.0, &generator)
+            .is_err());
+        assert!(neural_net
+            .predict_dropconnect(&vec![1.0], 0.5, &generator)
+            .is_err());
+    }
+
+    #[test]
+    fn test_neural_net_predict_dropconnect_wrong_input_size() {
+        let topology: Topology = TopologyBuilder::new()
+            .nb_input(2)
+            .add_layer(1, Box::new(TestActivationFn::default()))
+            .build()
+            .unwrap();
+
+        let neural_net: NeuralNet = NeuralNet::new(topology, &FractionGenerator::default());
+        let generator: UniformSequenceGenerator = UniformSequenceGenerator::default();
+
+        match neural_net.predict_dropconnect(&vec![1.0, 2.0, 3.0], 0.5, &generator) {
+            Ok(_) => assert!(false),
+            Err(message) => assert_eq!(
+                message,
+                String::from(NenufarError::DimensionMismatch {
+                    expected: 2,
+                    found: 3
+                })
+            ),
+        }
+    }
+
+    #[test]
+    fn test_neural_net_mc_dropout_predict() {
+        let topology: Topology = TopologyBuilder::new()
+            .nb_input(2)
+            .add_layer(4, Box::new(TestActivationFn::default()))
+            .add_layer(1, Box::new(TestActivationFn::default()))
+            .build()
+            .unwrap();
+
+        let neural_net: NeuralNet = NeuralNet::new(topology, &FractionGenerator::default());
+        let input: Vec<f64> = vec![1.0, 2.0];
+        let generator: UniformSequenceGenerator = UniformSequenceGenerator::default();
+
+        match neural_net.mc_dropout_predict(&input, &[0.0, 0.0], 20, &generator) {
+            Ok((mean, variance)) => {
+                let output: blaf::ColumnVector = neural_net.predict(&input).unwrap();
+
+                assert!(approx_equal(mean[0], output[0], 1e-9));
+                assert!(variance[0].abs() < 1e-12);
+            }
+            Err(_) => assert!(false),
+        }
+
+        match neural_net.mc_dropout_predict(&input, &[0.5, 0.0], 50, &generator) {
+            Ok((_, variance)) => assert!(variance[0] > 0.0),
+            Err(_) => assert!(false),
+        }
+
+        assert!(neural_net
+            .mc_dropout_predict(&input, &[0.5], 10, &generator)
+            .is_err());
+        assert!(neural_net
+            .mc_dropout_predict(&input, &[0.5, 0.0], 0, &generator)
+            .is_err());
+    }
+
+    #[test]
+    fn test_neural_net_layer_ablation() {
+        let topology: Topology = TopologyBuilder::new()
+            .nb_input(2)
+            .add_layer(2, Box::new(TestActivationFn::default()))
+            .add_layer(1, Box::new(TestActivationFn::default()))
+            .build()
+            .unwrap();
+
+        let neural_net: NeuralNet = NeuralNet::new(topology, &FractionGenerator::default());
+
+        let inputs: Vec<blaf::ColumnVector> = (0..5)
+            .map(|index| vec![index as f64 * 0.2, 1.0 - index as f64 * 0.1])
+            .collect();
+
+        // Targets are predictions, so loss of neural network is zero
+        let targets: Vec<blaf::ColumnVector> = inputs
+            .iter()
+            .map(|input| neural_net.predict(input).unwrap())
+            .collect();
+
+        let loss: LogCosh = LogCosh::default();
+
+        // First layer is square so it is replaced by identity
+        match neural_net.layer_ablation(&inputs, &targets, &loss, 0) {
+            Ok(increase) => assert!(increase > 0.1),
+            Err(_) => assert!(false),
+        }
+
+        // Last layer is not square so its output is zeroed
+        match neural_net.layer_ablation(&inputs, &targets, &loss, 1) {
+            Ok(increase) => {
+                let reference: f64 = targets
+                    .iter()
+                    .map(|target| loss.loss(&vec![0.0], target))
+                    .sum::<f64>()
+                    / targets.len() as f64;
+
+                assert!(approx_equal(increase, reference, 1e-9));
+            }
+            Err(_) => assert!(false),
+        }
+
+        assert!(neural_net
+            .layer_ablation(&inputs, &targets, &loss, 2)
+            .is_err());
+    }
+
+    // Hyperbolic tangent activation function for test
+    #[derive(Default, Clone)]
+    struct TestTanhFn {}
+
+    impl ActivationFunction for TestTanhFn {
+        fn activate(&self, x: f64) -> f64 {
+            return x.tanh();
+        }
+    }
+
+    // Number generator to fill matrix with small numbers
+    #[derive(Default)]
+    struct SmallGenerator {}
+
+    impl NumberGenerator for SmallGenerator {
+        fn generate_vec(&self, size: usize) -> Vec<f64> {
+            return vec![0.01; size];
+        }
+    }
+
+    #[test]
+    fn test_neural_net_saturation_report() {
+        let build_topology = || -> Topology {
+            return TopologyBuilder::new()
+                .nb_input(2)
+                .add_layer(3, Box::new(TestTanhFn::default()))
+                .add_layer(1, Box::new(TestTanhFn::default()))
+                .build()
+                .unwrap();
+        };
+
+        let inputs: Vec<blaf::ColumnVector> = vec![vec![1.0, 2.0], vec![-0.5, 0.3]];
+
+        let large_neural_net: NeuralNet =
+            NeuralNet::new(build_topology(), &LargeGenerator::default());
+
+        match large_neural_net.saturation_report(&inputs, 0.99) {
+            Ok(fractions) => {
+                assert_eq!(fractions.len(), 2);
+                assert!(fractions.iter().all(|&fraction| fraction > 0.9));
+            }
+            Err(_) => assert!(false),
+        }
+
+        let small_neural_net: NeuralNet =
+            NeuralNet::new(build_topology(), &SmallGenerator::default());
+
+        match small_neural_net.saturation_report(&inputs, 0.99) {
+            Ok(fractions) => {
+                assert_eq!(fractions.len(), 2);
+                assert!(fractions.iter().all(|&fraction| fraction < 0.1));
+            }
+            Err(_) => assert!(false),
+        }
+
+        assert!(small_neural_net.saturation_report(&[], 0.99).is_err());
+    }
+
+    #[test]
+    fn test_neural_net_to_c_header() {
+        let topology: Topology = TopologyBuilder::new()
+            .nb_input(2)
+            .add_layer(3, Box::new(TestActivationFn::default()))
+            .add_layer(1, Box::new(TestActivationFn::default()))
+            .build()
+            .unwrap();
+
+        let neural_net: NeuralNet = NeuralNet::new(topology, &SequenceGenerator::default());
+
+        let header: String = neural_net.to_c_header("model").unwrap();
+
+        assert!(header.starts_with("#ifndef MODEL_H\n#define MODEL_H\n"));
+        assert!(header.contains("static const unsigned int model_nb_layers = 2;"));
+        assert!(header.contains("static const unsigned int model_layer_dimensions[3] = {2, 3, 1};"));
+        assert!(header
+            .contains("static const double model_weights_0[6] = {0.0, 1.0, 2.0, 3.0, 4.0, 5.0};"));
+        assert!(header.contains("static const double model_bias_0[3] = {0.0, 1.0, 2.0};"));
+        assert!(header.contains("static const double model_weights_1[3] = {0.0, 1.0, 2.0};"));
+        assert!(header.contains("static const double model_bias_1[1] = {0.0};"));
+        assert!(header.ends_with("#endif // MODEL_H\n"));
+
+        assert!(neural_net.to_c_header("_model_2").is_ok());
+        assert!(neural_net.to_c_header("").is_err());
+        assert!(neural_net.to_c_header("2model").is_err());
+        assert!(neural_net.to_c_header("my-model").is_err());
+    }
+
+    #[test]
+    fn test_neural_net_to_c_header_non_finite_weights() {
+        let topology: Topology = TopologyBuilder::new()
+            .nb_input(2)
+            .add_layer(1, Box::new(TestActivationFn::default()))
+            .build()
+            .unwrap();
+
+        let mut neural_net: NeuralNet = NeuralNet::new(topology, &SequenceGenerator::default());
+
+        neural_net.weigths[0].set(0, 1, f64::NAN).unwrap();
+        assert!(neural_net.to_c_header("model").is_err());
+
+        neural_net.weigths[0].set(0, 1, 1.0).unwrap();
+        neural_net.bias[0][0] = f64::INFINITY;
+        assert!(neural_net.to_c_header("model").is_err());
+    }
+
+    // Number generator so that second neuron of first layer is twice first one plus one
+    #[derive(Default)]
+    struct CorrelatedGenerator {}
+
+    impl NumberGenerator for CorrelatedGenerator {
+        fn generate_vec(&self, size: usize) -> Vec<f64> {
+            if size == 6 {
+                return vec![1.0, 0.0, 2.0, 0.0, -1.0, 0.0];
+            }
+
+            return vec![0.0, 1.0, 0.0];
+        }
+    }
+
+    #[test]
+    fn test_neural_net_activation_correlation() {
+        let topology: Topology = TopologyBuilder::new()
+            .nb_input(2)
+            .add_layer(3, Box::new(TestActivationFn::default()))
+            .build()
+            .unwrap();
+
+        // Neurons output x0, 2 * x0 + 1 and -x0
+        let neural_net: NeuralNet = NeuralNet::new(topology, &CorrelatedGenerator::default());
+
+        let inputs: Vec<blaf::ColumnVector> = vec![
+            vec![1.0, 0.5],
+            vec![-2.0, 3.0],
+            vec![0.5, -1.0],
+            vec![4.0, 2.0],
+        ];
+
+        match neural_net.activation_correlation(&inputs, 0) {
+            Ok(correlations) => {
+                assert_eq!(correlations.nb_rows(), 3);
+                assert_eq!(correlations.nb_columns(), 3);
+
+                assert!(approx_equal(correlations.row(0)[0], 1.0, 1e-9));
+                assert!(approx_equal(correlations.row(0)[1], 1.0, 1e-9));
+                assert!(approx_equal(correlations.row(1)[0], 1.0, 1e-9));
+                assert!(approx_equal(correlations.row(0)[2], -1.0, 1e-9));
+                assert!(approx_equal(correlations.row(2)[1], -1.0, 1e-9));
+            }
+            Err(_) => assert!(false),
+        }
+
+        assert!(neural_net.activation_correlation(&inputs, 1).is_err());
+        assert!(neural_net.activation_correlation(&[], 0).is_err());
+    }
+
+    #[test]
+    fn test_neural_net_fit_output_bias_closed_form() {
+        let topology: Topology = TopologyBuilder::new()
+            .nb_input(2)
+            .add_layer(3, Box::new(TestTanhFn::default()))
+            .add_layer(1, Box::new(TestActivationFn::default()))
+            .build()
+            .unwrap();
+
+        let mut neural_net: NeuralNet = NeuralNet::new(topology, &FractionGenerator::default());
+
+        let inputs: Vec<blaf::ColumnVector> = vec![
+            vec![1.0, 0.5],
+            vec![-2.0, 3.0],
+            vec![0.5, -1.0],
+            vec![4.0, 2.0],
+        ];
+        let targets: Vec<blaf::ColumnVector> = vec![vec![1.0], vec![-3.0], vec![0.5], vec![7.0]];
+
+        match neural_net.fit_output_bias_closed_form(&inputs, &targets) {
+            Ok(_) => {
+                let mean_residual: f64 = inputs
+                    .iter()
+                    .zip(targets.iter())
+                    .map(|(input, target)| target[0] - neural_net.predict(input).unwrap()[0])
+                    .sum::<f64>()
+                    / inputs.len() as f64;
+
+                assert!(mean_residual.abs() < 1e-12);
+            }
+            Err(_) => assert!(false),
+        }
+
+        assert!(neural_net
+            .fit_output_bias_closed_form(&inputs, &targets[1..])
+            .is_err());
+        assert!(neural_net
+            .fit_output_bias_closed_form(&inputs[..1], &[vec![1.0, 2.0]])
+            .is_err());
+    }
+
+    #[test]
+    fn test_neural_net_fit_output_bias_closed_form_should_return_error() {
+        let topology: Topology = TopologyBuilder::new()
+            .nb_input(2)
+            .add_layer(2, Box::new(TestActivationFn::default()))
+            .build()
+            .unwrap();
+
+        let mut neural_net: NeuralNet = NeuralNet::new(topology, &OneGenerator::default());
+
+        assert!(neural_net
+            .fit_output_bias_closed_form(&[vec![1.0, 2.0]], &[vec![1.0, 2.0]])
+            .is_err());
+    }
+
+    #[test]
+    fn test_neural_net_entropy_gradient() {
+        let topology: Topology = TopologyBuilder::new()
+            .nb_input(2)
+            .add_layer(3, Box::new(TestTanhFn::default()))
+            .add_layer(3, Box::new(TestActivationFn::default()))
+            .build()
+            .unwrap();
+
+        let neural_net: NeuralNet = NeuralNet::new(topology, &FractionGenerator::default());
+        let input: blaf::ColumnVector = vec![0.3, -0.8];
+
+        match neural_net.entropy_gradient(&input) {
+            Ok(gradient) => {
+                assert_eq!(gradient.len(), input.len());
+
+                let h: f64 = 1e-6;
+
+                for id in 0..input.len() {
+                    let mut forward: blaf::ColumnVector = input.clone();
+                    forward[id] += h;
+
+                    let mut backward: blaf::ColumnVector = input.clone();
+                    backward[id] -= h;
+
+                    let entropies: Vec<f64> =
+                        neural_net.batch_entropy(&[forward, backward]).unwrap();
+                    let reference: f64 = (entropies[0] - entropies[1]) / (2.0 * h);
+
+                    assert!((gradient[id] - reference).abs() < 1e-6);
+                }
+            }
+            Err(_) => assert!(false),
+        }
+
+        assert!(neural_net.entropy_gradient(&vec![1.0]).is_err());
+    }
+
+    #[test]
+    fn test_neural_net_jacobian_sparsity() {
+        let topology: Topology = TopologyBuilder::new()
+            .nb_input(3)
+            .add_layer(2, Box::new(TestReluFn::default()))
+            .add_layer(2, Box::new(TestActivationFn::default()))
+            .build()
+            .unwrap();
+
+        let mut neural_net: NeuralNet = NeuralNet::new(topology, &ZeroGenerator::default());
+
+        // First input feeds only first output, two last inputs feed only second output
+        let mut state: NetworkState = neural_net.state();
+        state.weigths[0] = vec![1.0, 0.0, 0.0, 0.0, 2.0, -1.0];
+        state.weigths[1] = vec![3.0, 0.0, 0.0, 1.0];
+        neural_net.load_state(state).unwrap();
+
+        match neural_net.jacobian_sparsity(&vec![1.0, 1.0, 0.5], 1e-9) {
+            Ok(sparsity) => {
+                assert_eq!(
+                    sparsity,
+                    vec![vec![true, false, false], vec![false, true, true]]
+                );
+            }
+            Err(_) => assert!(false),
+        }
+
+        // Second hidden neuron is inactive, so second output does not depend on input
+        match neural_net.jacobian_sparsity(&vec![1.0, -1.0, 0.5], 1e-9) {
+            Ok(sparsity) => {
+                assert_eq!(
+                    sparsity,
+                    vec![vec![true, false, false], vec![false, false, false]]
+                );
+            }
+            Err(_) => assert!(false),
+        }
+
+        assert!(neural_net.jacobian_sparsity(&vec![1.0], 1e-9).is_err());
+    }
+
+    #[test]
+    fn test_neural_net_lrp_on_linear_network() {
+        let topology: Topology = TopologyBuilder::new()
+            .nb_input(3)
+            .add_layer(2, Box::new(TestActivationFn::default()))
+            .add_layer(2, Box::new(TestActivationFn::default()))
+            .build()
+            .unwrap();
+
+        let mut neural_net: NeuralNet = NeuralNet::new(topology, &ZeroGenerator::default());
+
+        let mut state: NetworkState = neural_net.state();
+        state.weigths[0] = vec![1.0, 2.0, -1.0, 0.5, 1.0, 3.0];
+        state.weigths[1] = vec![2.0, 1.0, -1.0, 4.0];
+        neural_net.load_state(state).unwrap();
+
+        // Folded weights of linear network W1 * W0
+        let folded: Vec<blaf::ColumnVector> = vec![vec![2.5, 5.0, 1.0], vec![1.0, 2.0, 13.0]];
+
+        let input: blaf::ColumnVector = vec![1.0, -0.5, 2.0];
+
+        for output_index in 0..2 {
+            match neural_net.lrp(&input, output_index, 1e-9) {
+                Ok(relevance) => {
+                    assert_eq!(relevance.len(), input.len());
+
+                    for id in 0..input.len() {
+                        let reference: f64 = input[id] * folded[output_index][id];
+                        assert!((relevance[id] - reference).abs() < 1e-6);
+                    }
+
+                    // Relevance is conserved from output to inputs
+                    let output: blaf::ColumnVector = neural_net.predict(&input).unwrap();
+                    let total: f64 = relevance.iter().sum::<f64>();
+                    assert!(approx_equal(total, output[output_index], 0.01));
+                }
+                Err(_) => assert!(false),
+            }
+        }
+
+        assert!(neural_net.lrp(&input, 2, 1e-9).is_err());
+        assert!(neural_net.lrp(&vec![1.0], 0, 1e-9).is_err());
+    }
+
+    #[test]
+    fn test_neural_net_jacobian_norm() {
+        let topology: Topology = TopologyBuilder::new()
+            .nb_input(3)
+            .add_layer(2, Box::new(TestActivationFn::default()))
+            .add_layer(2, Box::new(TestActivationFn::default()))
+            .build()
+            .unwrap();
+
+        let mut neural_net: NeuralNet = NeuralNet::new(topology, &FractionGenerator::default());
+
+        let mut state: NetworkState = neural_net.state();
+        state.weigths[0] = vec![1.0, 2.0, -1.0, 0.5, 1.0, 3.0];
+        state.weigths[1] = vec![2.0, 1.0, -1.0, 4.0];
+        neural_net.load_state(state).unwrap();
+
+        // Folded weights of linear network are [[2.5, 5, 1], [1, 2, 13]]
+        let reference: f64 = (2.5_f64.powi(2) + 25.0 + 1.0 + 1.0 + 4.0 + 169.0).sqrt();
+
+        for input in [
+            vec![1.0, -0.5, 2.0],
+            vec![0.0, 0.0, 0.0],
+            vec![-3.0, 7.0, 0.1],
+        ]
+        .iter()
+        {
+            match neural_net.jacobian_norm(input) {
+                Ok(norm) => assert!(approx_equal(norm, reference, 1e-9)),
+                Err(_) => assert!(false),
+            }
+        }
+
+        assert!(neural_net.jacobian_norm(&vec![1.0]).is_err());
+    }
+
+    #[test]
+    fn test_neural_net_gradient_snr() {
+        let topology: Topology = TopologyBuilder::new()
+            .nb_input(1)
+            .add_layer(1, Box::new(TestActivationFn::default()))
+            .build()
+            .unwrap();
+
+        // Output of neural network is zero
+        let neural_net: NeuralNet = NeuralNet::new(topology, &ZeroGenerator::default());
+        let loss: MeanSquaredError = MeanSquaredError::default();
+
+        // All samples give the same gradients, so every parameter is skipped
+        let inputs: Vec<blaf::ColumnVector> = vec![vec![1.0]; 4];
+        let targets: Vec<blaf::ColumnVector> = vec![vec![1.0]; 4];
+
+        assert!(neural_net.gradient_snr(&inputs, &targets, &loss).is_err());
+
+        // Gradients of samples cancel out, they have a zero mean
+        let targets: Vec<blaf::ColumnVector> = vec![vec![1.0], vec![-1.0], vec![1.5], vec![-1.5]];
+
+        match neural_net.gradient_snr(&inputs, &targets, &loss) {
+            Ok(snr) => assert!(snr.abs() < 1e-12),
+            Err(_) => assert!(false),
+        }
+
+        assert!(neural_net
+            .gradient_snr(&inputs, &targets[..2], &loss)
+            .is_err());
+        assert!(neural_net.gradient_snr(&[], &[], &loss).is_err());
+    }
+
+    #[test]
+    fn test_neural_net_gradient_snr_with_constant_gradient() {
+        let topology: Topology = TopologyBuilder::new()
+            .nb_input(1)
+            .add_layer(1, Box::new(TestActivationFn::default()))
+            .build()
+            .unwrap();
+
+        // Output of neural network is zero
+        let neural_net: NeuralNet = NeuralNet::new(topology, &ZeroGenerator::default());
+        let loss: MeanSquaredError = MeanSquaredError::default();
+
+        // Bias gradient is always -2, it is skipped. Weight gradients are -2, -4, -6
+        // and -8, with mean -5 and variance 5
+        let inputs: Vec<blaf::ColumnVector> = vec![vec![1.0], vec![2.0], vec![3.0], vec![4.0]];
+        let targets: Vec<blaf::ColumnVector> = vec![vec![1.0]; 4];
+
+        match neural_net.gradient_snr(&inputs, &targets, &loss) {
+            Ok(snr) => assert!(approx_equal(snr, 5.0_f64.sqrt(), 1e-9)),
+            Err(_) => assert!(false),
+        }
+    }
+
+    #[test]
+    fn test_neural_net_neuron_importance() {
+        let topology: Topology = TopologyBuilder::new()
+            .nb_input(2)
+            .add_layer(3, Box::new(TestTanhFn::default()))
+            .add_layer(1, Box::new(TestActivationFn::default()))
+            .build()
+            .unwrap();
+
+        let mut neural_net: NeuralNet = NeuralNet::new(topology, &FractionGenerator::default());
+
+        // Last hidden neuron is unused since its outgoing weight is zero
+        neural_net.weigths[1] = blaf::Matrix::from_vec(1, 3, vec![0.5, -0.3, 0.0]).unwrap();
+
+        let inputs: Vec<blaf::ColumnVector> = vec![vec![1.0, 0.5], vec![-2.0, 3.0]];
+        let targets: Vec<blaf::ColumnVector> = vec![vec![4.0], vec![-3.0]];
+
+        match neural_net.neuron_importance(&inputs, &targets, &LogCosh::default()) {
+            Ok(importances) => {
+                assert_eq!(importances.len(), 2);
+                assert_eq!(importances[0].len(), 3);
+                assert_eq!(importances[1].len(), 1);
+
+                assert!(importances[0][0] > 0.01);
+                assert!(importances[0][1] > 0.01);
+                assert!(importances[0][2].abs() < 1e-12);
+
+                // Importance of output neuron is absolute loss gradient
+                let reference: f64 = inputs
+                    .iter()
+                    .zip(targets.iter())
+                    .map(|(input, target)| {
+                        return (neural_net.predict(input).unwrap()[0] - target[0])
+                            .tanh()
+                            .abs();
+                    })
+                    .sum::<f64>();
+
+                assert!(approx_equal(importances[1][0], reference, 1e-9));
+            }
+            Err(_) => assert!(false),
+        }
+
+        assert!(neural_net
+            .neuron_importance(&inputs, &targets[1..], &LogCosh::default())
+            .is_err());
+    }
+
+    #[test]
+    fn test_neural_net_minimal_adversarial_perturbation() {
+        let topology: Topology = TopologyBuilder::new()
+            .nb_input(2)
+            .add_layer(2, Box::new(TestActivationFn::default()))
+            .build()
+            .unwrap();
+
+        let mut neural_net: NeuralNet = NeuralNet::new(topology, &ZeroGenerator::default());
+
+        // Predicted class is index of greatest input
+        neural_net.weigths[0] = blaf::Matrix::from_vec(2, 2, vec![1.0, 0.0, 0.0, 1.0]).unwrap();
+
+        let input: Vec<f64> = vec![1.0, 0.8];
+
+        match neural_net.minimal_adversarial_perturbation(&input, 0, 100, 0.01, &LogCosh::default())
+        {
+            Ok((perturbed, distance)) => {
+                assert_eq!(class_of(&neural_net.predict(&perturbed).unwrap()), 1);
+                assert!(distance > 0.0);
+                assert!(distance < 0.5);
+            }
+            Err(_) => assert!(false),
+        }
+
+        assert!(neural_net
+            .minimal_adversarial_perturbation(&input, 0, 2, 0.01, &LogCosh::default())
+            .is_err());
+        assert!(neural_net
+            .minimal_adversarial_perturbation(&input, 2, 100, 0.01, &LogCosh::default())
+            .is_err());
+
+        // Input is not predicted as target class
+        assert!(neural_net
+            .minimal_adversarial_perturbation(&input, 1, 100, 0.01, &LogCosh::default())
+            .is_err());
+    }
+
+    #[test]
+    fn test_neural_net_predict_with_softmax_layer() {
+        let topology: Topology = TopologyBuilder::new()
+            .nb_input(2)
+            .add_layer(3, Box::new(TestTanhFn::default()))
+            .add_vector_layer(4, Box::new(Softmax::default()))
+            .build()
+            .unwrap();
+
+        let neural_net: NeuralNet = NeuralNet::new(topology, &FractionGenerator::default());
+
+        match neural_net.predict(&vec![1.0, -0.5]) {
+            Ok(output) => {
+                assert_eq!(output.len(), 4);
+                assert!(approx_equal(output.iter().sum::<f64>(), 1.0, 1e-12));
+                assert!(output.iter().all(|&elem| elem > 0.0));
+            }
+            Err(_) => assert!(false),
+        }
+    }
+
+    #[test]
+    fn test_neural_net_train_linear_regression() {
+        let topology: Topology = TopologyBuilder::new()
+            .nb_input(2)
+            .add_layer(1, Box::new(TestActivationFn::default()))
+            .build()
+            .unwrap();
+
+        let mut neural_net: NeuralNet = NeuralNet::new(topology, &ZeroGenerator::default());
+
+        // Target is 2 * x0 - x1 + 0.5
+        let samples: Vec<(blaf::ColumnVector, blaf::ColumnVector)> = (0..10)
+            .map(|index| {
+                let x0: f64 = index as f64 * 0.1;
+                let x1: f64 = ((index * index) % 7) as f64 * 0.1;
+
+                return (vec![x0, x1], vec![2.0 * x0 - x1 + 0.5]);
+            })
+            .collect();
+
+        let first_loss: f64 = neural_net.train(&samples, 0.1, 1).unwrap();
+
+        match neural_net.train(&samples, 0.1, 500) {
+            Ok(last_loss) => {
+                assert!(last_loss < first_loss);
+                assert!(last_loss < 1e-6);
+
+                let output: blaf::ColumnVector = neural_net.predict(&vec![0.3, 0.2]).unwrap();
+                assert!(approx_equal(output[0], 0.9, 0.01));
+            }
+            Err(_) => assert!(false),
+        }
+    }
+
+    #[test]
+    fn test_neural_net_train_with_hidden_layer() {
+        let topology: Topology = TopologyBuilder::new()
+            .nb_input(2)
+            .add_layer(4, Box::new(TestTanhFn::default()))
+            .add_layer(1, Box::new(TestActivationFn::default()))
+            .build()
+            .unwrap();
+
+        let mut neural_net: NeuralNet = NeuralNet::new(topology, &SmallGenerator::default());
+        neural_net.weigths[0] =
+            blaf::Matrix::from_vec(4, 2, vec![0.5, -0.3, 0.2, 0.4, -0.6, 0.1, 0.3, 0.3]).unwrap();
+
+        let samples: Vec<(blaf::ColumnVector, blaf::ColumnVector)> = vec![
+            (vec![0.0, 0.0], vec![0.0]),
+            (vec![0.0, 1.0], vec![1.0]),
+            (vec![1.0, 0.0], vec![1.0]),
+            (vec![1.0, 1.0], vec![1.0]),
+        ];
+
+        let first_loss: f64 = neural_net.train(&samples, 0.1, 1).unwrap();
+        let last_loss: f64 = neural_net.train(&samples, 0.1, 2000).unwrap();
+
+        assert!(last_loss < first_loss);
+        assert!(last_loss < 0.05);
+    }
+
+    #[test]
+    fn test_neural_net_memorization_accuracy() {
+        let topology: Topology = TopologyBuilder::new()
+            .nb_input(2)
+            .add_layer(16, Box::new(TestTanhFn::default()))
+            .add_layer(2, Box::new(TestActivationFn::default()))
+            .build()
+            .unwrap();
+
+        let generator: UniformSequenceGenerator = UniformSequenceGenerator::default();
+        let mut neural_net: NeuralNet = NeuralNet::new(topology, &generator);
+
+        // Weights are centered around zero to break symmetry of hidden neurons
+        let mut state: NetworkState = neural_net.state();
+        state.weigths[0]
+            .iter_mut()
+            .for_each(|w| *w = 2.0 * *w - 1.0);
+        state.weigths[1]
+            .iter_mut()
+            .for_each(|w| *w = 0.5 * *w - 0.25);
+        neural_net.load_state(state).unwrap();
+
+        let before: NetworkState = neural_net.state();
+
+        let inputs: Vec<blaf::ColumnVector> = (0..8)
+            .map(|index| {
+                let angle: f64 = index as f64 * std::f64::consts::PI / 4.0;
+                return vec![angle.cos(), angle.sin()];
+            })
+            .collect();
+
+        match neural_net.memorization_accuracy(&inputs, 0.05, 2000, &generator) {
+            Ok(accuracy) => assert!(accuracy >= 0.99),
+            Err(_) => assert!(false),
+        }
+
+        // Neural network is left unchanged
+        let after: NetworkState = neural_net.state();
+        assert_eq!(before.weigths, after.weigths);
+        assert_eq!(before.bias, after.bias);
+
+        assert!(neural_net
+            .memorization_accuracy(&[], 0.05, 10, &generator)
+            .is_err());
+    }
+
+    #[test]
+    fn test_neural_net_train_gradient_check() {
+        let build_neural_net = || -> NeuralNet {
+            let topology: Topology = TopologyBuilder::new()
+                .nb_input(2)
+                .add_layer(3, Box::new(TestTanhFn::default()))
+                .add_layer(2, Box::new(TestTanhFn::default()))
+                .build()
+                .unwrap();
+
+            return NeuralNet::new(topology, &FractionGenerator::default());
+        };
+
+        let input: blaf::ColumnVector = vec![0.4, -0.7];
+        let target: blaf::ColumnVector = vec![0.1, -0.2];
+        let loss: MeanSquaredError = MeanSquaredError::default();
+
+        // One step with a tiny learning rate gives gradient from weight change
+        let learning_rate: f64 = 1e-7;
+        let mut trained: NeuralNet = build_neural_net();
+        trained
+            .train(&[(input.clone(), target.clone())], learning_rate, 1)
+            .unwrap();
+
+        let before: NetworkState = build_neural_net().state();
+        let after: NetworkState = trained.state();
+
+        let h: f64 = 1e-6;
+
+        for layer in 0..2 {
+            for index in 0..before.weigths[layer].len() {
+                let gradient: f64 =
+                    (before.weigths[layer][index] - after.weigths[layer][index]) / learning_rate;
+
+                let mut perturbed: Vec<f64> = Vec::with_capacity(2);
+
+                for sign in [1.0, -1.0] {
+                    let mut neural_net: NeuralNet = build_neural_net();
+                    let mut state: NetworkState = neural_net.state();
+                    state.weigths[layer][index] += sign * h;
+                    neural_net.load_state(state).unwrap();
+
+                    perturbed.push(loss.loss(&neural_net.predict(&input).unwrap(), &target));
+                }
+
+                let reference: f64 = (perturbed[0] - perturbed[1]) / (2.0 * h);
+                assert!((gradient - reference).abs() < 1e-4);
+            }
+        }
+    }
+
+    #[test]
+    fn test_neural_net_train_should_return_error() {
+        let topology: Topology = TopologyBuilder::new()
+            .nb_input(2)
+            .add_layer(1, Box::new(TestActivationFn::default()))
+            .build()
+            .unwrap();
+
+        let mut neural_net: NeuralNet = NeuralNet::new(topology, &ZeroGenerator::default());
+
+        assert!(neural_net.train(&[], 0.1, 1).is_err());
+        assert!(neural_net
+            .train(&[(vec![1.0, 2.0], vec![1.0, 2.0])], 0.1, 1)
+            .is_err());
+        assert!(neural_net.train(&[(vec![1.0], vec![1.0])], 0.1, 1).is_err());
     }
 }
@@ -1,6 +1,73 @@
+pub mod he;
+pub mod normal;
+pub mod orthogonal;
+pub mod uniform;
+pub mod xavier;
+
+/// Compute initial state of a xorshift64* sequence from a seed
+/// Seed is mixed by splitmix64, and the only seed mixed to zero, a state on which
+/// xorshift stays forever, is given another fixed state
+pub(crate) fn seed_state(seed: u64) -> u64 {
+    let mut state: u64 = seed.wrapping_add(0x9E3779B97F4A7C15);
+    state = (state ^ (state >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+    state = (state ^ (state >> 27)).wrapping_mul(0x94D049BB133111EB);
+    state ^= state >> 31;
+
+    if state == 0 {
+        return 0x9E3779B97F4A7C15;
+    }
+
+    return state;
+}
+
+/// Generate next pseudo-random number of a xorshift64* sequence
+pub(crate) fn next_random(state: &mut u64) -> u64 {
+    *state ^= *state >> 12;
+    *state ^= *state << 25;
+    *state ^= *state >> 27;
+
+    return state.wrapping_mul(0x2545F4914F6CDD1D);
+}
+
+/// Generate next pseudo-random number uniformly distributed in [0, 1)
+pub(crate) fn next_uniform(state: &mut u64) -> f64 {
+    return (next_random(state) >> 11) as f64 / (1u64 << 53) as f64;
+}
+
+/// Generate next pseudo-random number with standard normal distribution,
+/// using Box-Muller transform
+pub(crate) fn next_normal(state: &mut u64) -> f64 {
+    let u1: f64 = 1.0 - next_uniform(state);
+    let u2: f64 = next_uniform(state);
+
+    return (-2.0 * u1.ln()).sqrt() * (2.0 * std::f64::consts::PI * u2).cos();
+}
+
 /// Number generator trait
 /// It allows not to depend on specific crate and lets
 /// the user free to implement its own generator
 pub trait NumberGenerator {
     fn generate_vec(&self, size: usize) -> Vec<f64>;
 }
+
+/// Layer aware number generator trait
+/// It allows to generate weights of a layer knowing its fan-in (number of inputs) and
+/// fan-out (number of neurons), as required by initialization schemes such as Xavier one.
+/// Every number generator is layer aware, it simply fills weights and bias of a layer
+pub trait LayerAwareGenerator {
+    fn generate_layer(&self, fan_in: usize, fan_out: usize) -> Vec<f64>;
+    fn generate_bias(&self, size: usize) -> Vec<f64>;
+}
+
+impl<Generator> LayerAwareGenerator for Generator
+where
+    Generator: NumberGenerator,
+{
+    fn generate_layer(&self, fan_in: usize, fan_out: usize) -> Vec<f64> {
+        return self.generate_vec(fan_in * fan_out);
+    }
+
+    fn generate_bias(&self, size: usize) -> Vec<f64> {
+        return self.generate_vec(size);
+    }
+}
@@ -0,0 +1,64 @@
+use super::normal::NormalGenerator;
+use super::{LayerAwareGenerator, NumberGenerator};
+
+/// He (Kaiming) weight initialization, suited to ReLU activation functions
+/// Weights of a layer are normally distributed with zero mean and standard deviation
+/// sqrt(2 / fan_in), and bias are zeros
+pub struct HeGenerator {
+    normal: NormalGenerator,
+}
+
+impl HeGenerator {
+    /// Construct a He weight generator from a seed
+    pub fn new(seed: u64) -> Self {
+        return Self {
+            normal: NormalGenerator::new(0.0, 1.0, seed),
+        };
+    }
+}
+
+impl LayerAwareGenerator for HeGenerator {
+    fn generate_layer(&self, fan_in: usize, fan_out: usize) -> Vec<f64> {
+        let std_dev: f64 = (2.0 / fan_in as f64).sqrt();
+
+        return self
+            .normal
+            .generate_vec(fan_in * fan_out)
+            .iter()
+            .map(|x| std_dev * x)
+            .collect();
+    }
+
+    fn generate_bias(&self, size: usize) -> Vec<f64> {
+        return vec![0.0; size];
+    }
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_he_generator_variance() {
+        let generator: HeGenerator = HeGenerator::new(9);
+        let fan_in: usize = 250;
+        let fan_out: usize = 400;
+
+        let weigths: Vec<f64> = generator.generate_layer(fan_in, fan_out);
+        assert_eq!(weigths.len(), fan_in * fan_out);
+
+        let size: f64 = weigths.len() as f64;
+        let mean: f64 = weigths.iter().sum::<f64>() / size;
+        let variance: f64 = weigths.iter().map(|w| (w - mean).powi(2)).sum::<f64>() / size;
+
+        assert!(mean.abs() < 1e-3);
+        assert!((variance - 2.0 / fan_in as f64).abs() < 2e-4);
+    }
+
+    #[test]
+    fn test_he_generator_bias() {
+        let generator: HeGenerator = HeGenerator::new(9);
+        assert_eq!(generator.generate_bias(3), vec![0.0; 3]);
+    }
+}
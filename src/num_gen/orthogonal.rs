@@ -0,0 +1,71 @@
+use super::super::blaf::Matrix;
+use super::normal::NormalGenerator;
+use super::LayerAwareGenerator;
+
+/// Orthogonal weight initialization, suited to deep stacks of layers as it preserves
+/// gradient norms. Weights of a layer are a normally distributed random matrix
+/// orthogonalized by Gram-Schmidt process along its smallest dimension, then scaled by
+/// a gain, and bias are zeros
+pub struct OrthogonalGenerator {
+    gain: f64,
+    normal: NormalGenerator,
+}
+
+impl OrthogonalGenerator {
+    /// Construct an orthogonal weight generator from a gain and a seed
+    pub fn new(gain: f64, seed: u64) -> Self {
+        return Self {
+            gain,
+            normal: NormalGenerator::new(0.0, 1.0, seed),
+        };
+    }
+}
+
+impl LayerAwareGenerator for OrthogonalGenerator {
+    /// Generate weights of a layer as a matrix of fan_out rows and fan_in columns
+    /// Panics in the practically impossible case where normal numbers are linearly dependent
+    fn generate_layer(&self, fan_in: usize, fan_out: usize) -> Vec<f64> {
+        let weigths: Matrix = Matrix::new_orthogonal(fan_out, fan_in, self.gain, &self.normal)
+            .expect("Normally distributed numbers should be linearly independent");
+
+        return weigths.data().to_vec();
+    }
+
+    fn generate_bias(&self, size: usize) -> Vec<f64> {
+        return vec![0.0; size];
+    }
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::super::super::blaf::gemm;
+    use super::*;
+
+    #[test]
+    fn test_orthogonal_generator_non_square_layers() {
+        let generator: OrthogonalGenerator = OrthogonalGenerator::new(2.0, 5);
+
+        // Wide layer has orthogonal rows, tall layer has orthogonal columns
+        for (fan_in, fan_out) in [(6, 3), (3, 6)] {
+            let weigths: Matrix =
+                Matrix::from_vec(fan_out, fan_in, generator.generate_layer(fan_in, fan_out))
+                    .unwrap();
+
+            let gram: Matrix = if fan_out < fan_in {
+                gemm(&weigths, &weigths.transpose()).unwrap()
+            } else {
+                gemm(&weigths.transpose(), &weigths).unwrap()
+            };
+
+            for row in 0..gram.nb_rows() {
+                for col in 0..gram.nb_columns() {
+                    let reference: f64 = if row == col { 4.0 } else { 0.0 };
+                    assert!((gram[(row, col)] - reference).abs() < 1e-9);
+                }
+            }
+        }
+
+        assert_eq!(generator.generate_bias(3), vec![0.0; 3]);
+    }
+}
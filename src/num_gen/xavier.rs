@@ -0,0 +1,68 @@
+use super::uniform::UniformGenerator;
+use super::{LayerAwareGenerator, NumberGenerator};
+
+/// Xavier (Glorot) weight initialization, suited to sigmoid and tanh activation functions
+/// Weights of a layer are uniformly distributed in [-limit, limit) with
+/// limit = sqrt(6 / (fan_in + fan_out)), and bias are zeros
+pub struct XavierGenerator {
+    uniform: UniformGenerator,
+}
+
+impl XavierGenerator {
+    /// Construct a Xavier weight generator from a seed
+    pub fn new(seed: u64) -> Self {
+        return Self {
+            uniform: UniformGenerator::new(-1.0, 1.0, seed),
+        };
+    }
+}
+
+impl LayerAwareGenerator for XavierGenerator {
+    fn generate_layer(&self, fan_in: usize, fan_out: usize) -> Vec<f64> {
+        let limit: f64 = (6.0 / (fan_in + fan_out) as f64).sqrt();
+
+        return self
+            .uniform
+            .generate_vec(fan_in * fan_out)
+            .iter()
+            .map(|x| limit * x)
+            .collect();
+    }
+
+    fn generate_bias(&self, size: usize) -> Vec<f64> {
+        return vec![0.0; size];
+    }
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_xavier_generator_variance() {
+        let generator: XavierGenerator = XavierGenerator::new(3);
+        let fan_in: usize = 300;
+        let fan_out: usize = 200;
+
+        let weigths: Vec<f64> = generator.generate_layer(fan_in, fan_out);
+        assert_eq!(weigths.len(), fan_in * fan_out);
+
+        let limit: f64 = (6.0 / 500.0_f64).sqrt();
+        assert!(weigths.iter().all(|&w| w >= -limit && w < limit));
+
+        // Variance of uniform distribution on [-limit, limit) is 2 / (fan_in + fan_out)
+        let size: f64 = weigths.len() as f64;
+        let mean: f64 = weigths.iter().sum::<f64>() / size;
+        let variance: f64 = weigths.iter().map(|w| (w - mean).powi(2)).sum::<f64>() / size;
+
+        assert!(mean.abs() < 1e-3);
+        assert!((variance - 2.0 / 500.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_xavier_generator_bias() {
+        let generator: XavierGenerator = XavierGenerator::new(3);
+        assert_eq!(generator.generate_bias(4), vec![0.0; 4]);
+    }
+}
@@ -0,0 +1,196 @@
+/// Optimizer trait
+/// It allows to define how parameters are updated from their gradients
+pub trait Optimizer {
+    fn step(&mut self, parameters: &mut [f64], gradients: &[f64]) -> Result<(), String>;
+}
+
+/// Adagrad optimizer
+/// Learning rate of each parameter is divided by square root of the sum of its squared
+/// gradients, which is well suited for sparse features
+pub struct Adagrad {
+    pub learning_rate: f64,
+    pub epsilon: f64,
+    pub accumulated_squares: Vec<f64>,
+}
+
+impl Adagrad {
+    /// Construct an Adagrad optimizer without accumulated squared gradients
+    pub fn new(learning_rate: f64, epsilon: f64) -> Self {
+        return Self {
+            learning_rate,
+            epsilon,
+            accumulated_squares: Vec::new(),
+        };
+    }
+}
+
+impl Optimizer for Adagrad {
+    fn step(&mut self, parameters: &mut [f64], gradients: &[f64]) -> Result<(), String> {
+        if parameters.len() != gradients.len() {
+            return Err("Number of parameters and gradients must be equal".to_string());
+        }
+
+        if self.accumulated_squares.is_empty() {
+            self.accumulated_squares = vec![0.0; parameters.len()];
+        }
+
+        if self.accumulated_squares.len() != parameters.len() {
+            return Err(
+                "Number of parameters is not consistent with previous steps of optimizer"
+                    .to_string(),
+            );
+        }
+
+        parameters
+            .iter_mut()
+            .zip(gradients.iter())
+            .zip(self.accumulated_squares.iter_mut())
+            .for_each(|((parameter, gradient), accumulated_square)| {
+                *accumulated_square += gradient * gradient;
+                *parameter -=
+                    self.learning_rate * gradient / (accumulated_square.sqrt() + self.epsilon);
+            });
+
+        return Ok(());
+    }
+}
+
+/// Applier of gradients computed asynchronously against past versions of parameters
+/// Version of parameters is increased at each applied update. An update computed against
+/// a version which is s updates old (staleness) is scaled by 1 / (1 + s)
+pub struct StaleGradientApplier {
+    pub learning_rate: f64,
+    pub version: usize,
+}
+
+impl StaleGradientApplier {
+    /// Construct a stale gradient applier at version zero
+    pub fn new(learning_rate: f64) -> Self {
+        return Self {
+            learning_rate,
+            version: 0,
+        };
+    }
+
+    /// Compute scaling factor of an update according to its staleness
+    pub fn staleness_factor(staleness: usize) -> f64 {
+        return 1.0 / (1.0 + staleness as f64);
+    }
+
+    /// Apply gradients computed against given version of parameters
+    /// This function return staleness of applied gradients
+    pub fn apply(
+        &mut self,
+        parameters: &mut [f64],
+        gradients: &[f64],
+        gradient_version: usize,
+    ) -> Result<usize, String> {
+        if parameters.len() != gradients.len() {
+            return Err("Number of parameters and gradients must be equal".to_string());
+        }
+
+        if gradient_version > self.version {
+            return Err(format!(
+                "Gradients are computed against version {} but current version is {}",
+                gradient_version, self.version
+            ));
+        }
+
+        let staleness: usize = self.version - gradient_version;
+        let scale: f64 = self.learning_rate * Self::staleness_factor(staleness);
+
+        parameters
+            .iter_mut()
+            .zip(gradients.iter())
+            .for_each(|(parameter, gradient)| *parameter -= scale * gradient);
+
+        self.version += 1;
+
+        return Ok(staleness);
+    }
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Function to check if two numbers are approximatively equal
+    fn approx_equal(value: f64, reference: f64, precision: f64) -> bool {
+        let mut error: f64 = (value - reference).abs();
+
+        if reference != 0.0 {
+            error /= reference.abs();
+        }
+
+        return error < precision;
+    }
+
+    #[test]
+    fn test_adagrad_two_steps() {
+        let mut adagrad: Adagrad = Adagrad::new(0.1, 1e-8);
+        let mut parameters: Vec<f64> = vec![1.0, 2.0];
+
+        // Accumulated squares are [1, 4]
+        adagrad.step(&mut parameters, &[1.0, -2.0]).unwrap();
+
+        assert!(approx_equal(parameters[0], 0.9, 1e-6));
+        assert!(approx_equal(parameters[1], 2.1, 1e-6));
+
+        // Accumulated squares are [10, 4]
+        adagrad.step(&mut parameters, &[3.0, 0.0]).unwrap();
+
+        assert!(approx_equal(
+            parameters[0],
+            0.9 - 0.3 / 10.0_f64.sqrt(),
+            1e-6
+        ));
+        assert!(approx_equal(parameters[1], 2.1, 1e-6));
+        assert_eq!(adagrad.accumulated_squares, vec![10.0, 4.0]);
+    }
+
+    #[test]
+    fn test_adagrad_return_error() {
+        let mut adagrad: Adagrad = Adagrad::new(0.1, 1e-8);
+        let mut parameters: Vec<f64> = vec![1.0, 2.0];
+
+        assert!(adagrad.step(&mut parameters, &[1.0]).is_err());
+
+        adagrad.step(&mut parameters, &[1.0, 1.0]).unwrap();
+
+        let mut other_parameters: Vec<f64> = vec![1.0, 2.0, 3.0];
+        assert!(adagrad
+            .step(&mut other_parameters, &[1.0, 1.0, 1.0])
+            .is_err());
+    }
+
+    #[test]
+    fn test_stale_gradient_applier() {
+        let mut applier: StaleGradientApplier = StaleGradientApplier::new(0.5);
+        let mut parameters: Vec<f64> = vec![1.0, 2.0];
+
+        // Gradients computed against current version are applied at full strength
+        assert_eq!(applier.apply(&mut parameters, &[1.0, -2.0], 0), Ok(0));
+        assert!(approx_equal(parameters[0], 0.5, 1e-6));
+        assert!(approx_equal(parameters[1], 3.0, 1e-6));
+        assert_eq!(applier.version, 1);
+
+        applier.apply(&mut parameters, &[0.0, 0.0], 1).unwrap();
+
+        // Gradients computed two versions ago are down-weighted by 1 / 3
+        assert_eq!(applier.apply(&mut parameters, &[3.0, 0.0], 0), Ok(2));
+        assert!(approx_equal(parameters[0], 0.0, 1e-6));
+        assert!(approx_equal(parameters[1], 3.0, 1e-6));
+        assert_eq!(applier.version, 3);
+    }
+
+    #[test]
+    fn test_stale_gradient_applier_return_error() {
+        let mut applier: StaleGradientApplier = StaleGradientApplier::new(0.5);
+        let mut parameters: Vec<f64> = vec![1.0, 2.0];
+
+        assert!(applier.apply(&mut parameters, &[1.0], 0).is_err());
+        assert!(applier.apply(&mut parameters, &[1.0, 1.0], 1).is_err());
+        assert_eq!(applier.version, 0);
+    }
+}
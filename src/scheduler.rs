@@ -0,0 +1,83 @@
+/// Learning rate scheduler trait
+/// It allows to define how learning rate evolves along training epochs
+pub trait LearningRateScheduler {
+    fn learning_rate(&self, epoch: usize) -> f64;
+}
+
+/// Warmup then decay learning rate scheduler
+/// Learning rate linearly ramps from zero to peak learning rate over warmup epochs,
+/// then decays exponentially by given factor at each epoch
+pub struct WarmupDecay {
+    pub warmup_epochs: usize,
+    pub peak_lr: f64,
+    pub decay: f64,
+}
+
+impl LearningRateScheduler for WarmupDecay {
+    fn learning_rate(&self, epoch: usize) -> f64 {
+        if epoch < self.warmup_epochs {
+            return self.peak_lr * epoch as f64 / self.warmup_epochs as f64;
+        }
+
+        return self.peak_lr * self.decay.powi((epoch - self.warmup_epochs) as i32);
+    }
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Function to check if two numbers are approximatively equal
+    fn approx_equal(value: f64, reference: f64, precision: f64) -> bool {
+        let mut error: f64 = (value - reference).abs();
+
+        if reference != 0.0 {
+            error /= reference.abs();
+        }
+
+        return error < precision;
+    }
+
+    #[test]
+    fn test_warmup_decay_during_warmup() {
+        let scheduler: WarmupDecay = WarmupDecay {
+            warmup_epochs: 4,
+            peak_lr: 0.1,
+            decay: 0.5,
+        };
+
+        assert!(approx_equal(scheduler.learning_rate(0), 0.0, 0.01));
+        assert!(approx_equal(scheduler.learning_rate(1), 0.025, 0.01));
+        assert!(approx_equal(scheduler.learning_rate(2), 0.05, 0.01));
+        assert!(approx_equal(scheduler.learning_rate(4), 0.1, 0.01));
+    }
+
+    #[test]
+    fn test_warmup_decay_after_warmup() {
+        let scheduler: WarmupDecay = WarmupDecay {
+            warmup_epochs: 4,
+            peak_lr: 0.1,
+            decay: 0.5,
+        };
+
+        assert!(approx_equal(scheduler.learning_rate(5), 0.05, 0.01));
+        assert!(approx_equal(scheduler.learning_rate(6), 0.025, 0.01));
+
+        for epoch in 4..20 {
+            assert!(scheduler.learning_rate(epoch + 1) < scheduler.learning_rate(epoch));
+        }
+    }
+
+    #[test]
+    fn test_warmup_decay_without_warmup() {
+        let scheduler: WarmupDecay = WarmupDecay {
+            warmup_epochs: 0,
+            peak_lr: 0.1,
+            decay: 0.9,
+        };
+
+        assert!(approx_equal(scheduler.learning_rate(0), 0.1, 0.01));
+        assert!(approx_equal(scheduler.learning_rate(1), 0.09, 0.01));
+    }
+}
@@ -1,17 +1,20 @@
-use super::activation_fn::ActivationFunction;
+use super::activation_fn::{ActivationFunction, LayerActivation, VectorActivation};
+use super::error::NenufarError;
 
 /// Neural network topology is define by number of neurons in each layer (including input and
 /// output layer), then activation functions apply on each neurons belonging to a layer
+#[derive(Clone)]
 pub struct Topology {
     pub nb_neurons: Vec<usize>,
-    pub activation_functions: Vec<Box<dyn ActivationFunction>>,
+    pub activation_functions: Vec<LayerActivation>,
 }
 
 /// Neural network topology builder
 pub struct TopologyBuilder {
     nb_input: usize,
     nb_neurons: Vec<usize>,
-    activation_functions: Vec<Box<dyn ActivationFunction>>,
+    activation_functions: Vec<LayerActivation>,
+    output_layer_index: Option<usize>,
 }
 
 impl TopologyBuilder {
@@ -21,6 +24,7 @@ impl TopologyBuilder {
             nb_input: 0,
             nb_neurons: Vec::with_capacity(12),
             activation_functions: Vec::with_capacity(10),
+            output_layer_index: None,
         };
     }
 
@@ -31,29 +35,75 @@ impl TopologyBuilder {
     }
 
     /// Add a layer in topology by giving number of neurons and the activation function to apply
-    /// on each neuron
+    /// on each neuron. Layers are added in order after input layer, so the last added layer
+    /// is the output layer of neural network
     pub fn add_layer(
         mut self,
         nb_neuron: usize,
         activation_function: Box<dyn ActivationFunction>,
     ) -> Self {
         self.nb_neurons.push(nb_neuron);
-        self.activation_functions.push(activation_function);
+        self.activation_functions
+            .push(LayerActivation::Scalar(activation_function));
         return self;
     }
 
+    /// Add a layer in topology by giving number of neurons and the vector activation function
+    /// to apply on the whole layer, such as softmax
+    pub fn add_vector_layer(
+        mut self,
+        nb_neuron: usize,
+        activation_function: Box<dyn VectorActivation>,
+    ) -> Self {
+        self.nb_neurons.push(nb_neuron);
+        self.activation_functions
+            .push(LayerActivation::Vector(activation_function));
+        return self;
+    }
+
+    /// Add output layer in topology by giving number of outputs and the activation function
+    /// to apply on each output. No layer can be added after output layer
+    pub fn output_layer(
+        mut self,
+        nb_neuron: usize,
+        activation_function: Box<dyn ActivationFunction>,
+    ) -> Self {
+        if self.output_layer_index.is_none() {
+            self.output_layer_index = Some(self.nb_neurons.len());
+        }
+
+        return self.add_layer(nb_neuron, activation_function);
+    }
+
+    /// Add output layer in topology by giving number of outputs and the vector activation
+    /// function to apply on the whole layer, such as softmax. No layer can be added after
+    /// output layer
+    pub fn output_vector_layer(
+        mut self,
+        nb_neuron: usize,
+        activation_function: Box<dyn VectorActivation>,
+    ) -> Self {
+        if self.output_layer_index.is_none() {
+            self.output_layer_index = Some(self.nb_neurons.len());
+        }
+
+        return self.add_vector_layer(nb_neuron, activation_function);
+    }
+
     /// Build a neural network topology from pre-configured data
-    pub fn build(self) -> Result<Topology, String> {
+    pub fn build(self) -> Result<Topology, NenufarError> {
         if self.nb_input == 0 {
-            return Err(String::from(
-                "There is no input layer in your neural network",
-            ));
+            return Err(NenufarError::MissingInputLayer);
         }
 
-        if self.nb_neurons.len() == 0 {
-            return Err(String::from(
-                "There is no hidden layer in your neural network",
-            ));
+        if self.nb_neurons.is_empty() {
+            return Err(NenufarError::EmptyTopology);
+        }
+
+        if let Some(index) = self.output_layer_index {
+            if index + 1 != self.nb_neurons.len() {
+                return Err(NenufarError::MisplacedOutputLayer);
+            }
         }
 
         let mut nb_neurons: Vec<usize> = Vec::with_capacity(self.nb_neurons.len() + 1);
@@ -68,16 +118,24 @@ impl TopologyBuilder {
     }
 }
 
+impl Default for TopologyBuilder {
+    fn default() -> Self {
+        return Self::new();
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    use super::super::activation_fn::softmax::Softmax;
     use super::*;
 
     #[test]
     fn test_build_empty_topology() {
-        let topology: Result<Topology, String> = TopologyBuilder::new().build();
+        let topology: Result<Topology, NenufarError> = TopologyBuilder::new().build();
         assert!(topology.is_err());
     }
 
+    #[derive(Clone)]
     struct TestActivationFn {
         factor: f64,
     }
@@ -100,19 +158,19 @@ mod tests {
 
     #[test]
     fn test_build_topology_without_inputs() {
-        let topology: Result<Topology, String> = TopologyBuilder::new()
+        let topology: Result<Topology, NenufarError> = TopologyBuilder::new()
             .add_layer(2, Box::new(TestActivationFn::new(2.0)))
             .add_layer(1, Box::new(TestActivationFn::new(3.0)))
             .build();
 
-        assert!(topology.is_err());
+        assert!(matches!(topology, Err(NenufarError::MissingInputLayer)));
     }
 
     #[test]
     fn test_build_topology_without_layer() {
-        let topology: Result<Topology, String> = TopologyBuilder::new().nb_input(2).build();
+        let topology: Result<Topology, NenufarError> = TopologyBuilder::new().nb_input(2).build();
 
-        assert!(topology.is_err());
+        assert!(matches!(topology, Err(NenufarError::EmptyTopology)));
     }
 
     // Function to check if two numbers are approximatively equal
@@ -164,21 +222,87 @@ mod tests {
         let precision: f64 = 0.01;
 
         assert!(approx_equal(
-            topology.activation_functions[0].activate(1.0),
+            topology.activation_functions[0].activate(&vec![1.0])[0],
             factor_first_layer,
             precision
         ));
 
         assert!(approx_equal(
-            topology.activation_functions[1].activate(1.0),
+            topology.activation_functions[1].activate(&vec![1.0])[0],
             factor_second_layer,
             precision
         ));
 
         assert!(approx_equal(
-            topology.activation_functions[2].activate(1.0),
+            topology.activation_functions[2].activate(&vec![1.0])[0],
             factor_last_layer,
             precision
         ));
     }
+
+    #[test]
+    fn test_build_topology_with_output_layer() {
+        let topology: Topology = TopologyBuilder::new()
+            .nb_input(2)
+            .add_layer(3, Box::new(TestActivationFn::new(2.0)))
+            .output_layer(1, Box::new(TestActivationFn::new(5.0)))
+            .build()
+            .unwrap();
+
+        assert_eq!(topology.nb_neurons, vec![2, 3, 1]);
+        assert_eq!(
+            topology.activation_functions.len(),
+            topology.nb_neurons.len() - 1
+        );
+
+        assert!(approx_equal(
+            topology.activation_functions[1].activate(&vec![1.0])[0],
+            5.0,
+            0.01
+        ));
+    }
+
+    #[test]
+    fn test_build_topology_with_softmax_output_layer() {
+        let topology: Topology = TopologyBuilder::new()
+            .nb_input(2)
+            .add_layer(3, Box::new(TestActivationFn::new(2.0)))
+            .output_vector_layer(2, Box::new(Softmax::default()))
+            .build()
+            .unwrap();
+
+        assert_eq!(topology.nb_neurons, vec![2, 3, 2]);
+
+        let output: Vec<f64> = topology.activation_functions[1].activate(&vec![1.0, 1.0]);
+
+        assert!(approx_equal(output[0], 0.5, 0.01));
+        assert!(approx_equal(output[1], 0.5, 0.01));
+
+        let topology: Result<Topology, NenufarError> = TopologyBuilder::new()
+            .nb_input(2)
+            .output_vector_layer(2, Box::new(Softmax::default()))
+            .add_layer(3, Box::new(TestActivationFn::new(2.0)))
+            .build();
+
+        assert!(matches!(topology, Err(NenufarError::MisplacedOutputLayer)));
+    }
+
+    #[test]
+    fn test_build_topology_with_layer_after_output_layer() {
+        let topology: Result<Topology, NenufarError> = TopologyBuilder::new()
+            .nb_input(2)
+            .output_layer(1, Box::new(TestActivationFn::new(5.0)))
+            .add_layer(3, Box::new(TestActivationFn::new(2.0)))
+            .build();
+
+        assert!(matches!(topology, Err(NenufarError::MisplacedOutputLayer)));
+
+        let topology: Result<Topology, NenufarError> = TopologyBuilder::new()
+            .nb_input(2)
+            .output_layer(1, Box::new(TestActivationFn::new(5.0)))
+            .output_layer(1, Box::new(TestActivationFn::new(5.0)))
+            .build();
+
+        assert!(topology.is_err());
+    }
 }